@@ -0,0 +1,126 @@
+use std::fmt;
+
+use num::{BigInt, ToPrimitive};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Integer(i64),
+    BigInteger(BigInt),
+    Text(String),
+    // one string per row
+    Picture(Vec<String>),
+    // nothing to answer, e.g. a challenge that only asks to push a button
+    None,
+}
+
+impl Answer {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::BigInteger(_) => "big_integer",
+            Answer::Text(_) => "text",
+            Answer::Picture(_) => "picture",
+            Answer::None => "none",
+        }
+    }
+
+    // answers written by hand have no type, so compare them as they are displayed
+    pub fn matches(&self, expected: &str) -> bool {
+        let displayed = self.to_string();
+        displayed == expected
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => n.fmt(f),
+            Answer::BigInteger(n) => n.fmt(f),
+            Answer::Text(text) => text.fmt(f),
+            Answer::Picture(rows) => rows.join("\n").fmt(f),
+            Answer::None => write!(f, "none"),
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self.matches(other)
+    }
+}
+
+impl PartialEq<String> for Answer {
+    fn eq(&self, other: &String) -> bool {
+        self.matches(other)
+    }
+}
+
+impl From<BigInt> for Answer {
+    // keep the small ones small
+    fn from(n: BigInt) -> Self {
+        match n.to_i64() {
+            Some(n) => Answer::Integer(n),
+            None => Answer::BigInteger(n),
+        }
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                BigInt::from(n).into()
+            }
+        }
+    )*};
+}
+
+from_integer!(i32, i64, i128, u32, u64, u128, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from() {
+        assert_eq!(Answer::from(42usize), Answer::Integer(42));
+        assert_eq!(Answer::from(-7i32), Answer::Integer(-7));
+        assert_eq!(Answer::from(u64::MAX).kind(), "big_integer");
+        assert_eq!(
+            Answer::from("mxmxvkd,sqjhc"),
+            Answer::Text("mxmxvkd,sqjhc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        let picture = Answer::Picture(vec!["#.".to_string(), ".#".to_string()]);
+        assert_eq!(picture.to_string(), "#.\n.#");
+        assert_eq!(Answer::None.to_string(), "none");
+    }
+
+    #[test]
+    fn test_matches() {
+        assert_eq!(Answer::from(485739), "485739");
+        assert_ne!(Answer::from(485739), "485738");
+        assert_eq!(
+            Answer::Picture(vec!["#.".to_string(), ".#".to_string()]),
+            "#.\n.#"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::{collections::HashMap, fs::read_to_string};
+
+use crate::{
+    days::{
+        parse::{self, LineError},
+        Answer, Part,
+    },
+    error::{Error, Result},
+    report::DayResult,
+};
+
+// one "<day> <part> <answer>" per line, blank lines and # comments are ignored,
+// the rows of a picture answer are separated by a literal \n
+pub struct Answers(HashMap<(usize, Part), String>);
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    // nothing to compare with
+    Unknown,
+}
+
+impl Answers {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = read_to_string(path).map_err(|e| Error::Io {
+            path: path.to_string(),
+            kind: e.kind(),
+        })?;
+
+        Self::parse(&content)
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        let entries = parse::lines(s, 1, |line| {
+            if line.trim().is_empty() || line.starts_with('#') {
+                return Ok(None);
+            }
+
+            let mut tokens = line.splitn(3, ' ');
+            let day = parse::number(line, tokens.next().unwrap_or(line))?;
+            let part = match tokens.next() {
+                Some("1") => Part::First,
+                Some("2") => Part::Second,
+                Some(part) => return Err(LineError::at(line, part, "expected part 1 or 2")),
+                None => return Err(LineError::new(line.len() + 1, "missing part")),
+            };
+            let answer = match tokens.next() {
+                Some(answer) if !answer.is_empty() => answer,
+                _ => return Err(LineError::new(line.len() + 1, "missing answer")),
+            };
+
+            Ok(Some(((day, part), answer.replace("\\n", "\n"))))
+        })?;
+
+        Ok(Self(entries.into_iter().flatten().collect()))
+    }
+
+    pub fn get(&self, day: usize, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    // None when the part was not run
+    pub fn verdict(
+        &self,
+        day: usize,
+        part: Part,
+        result: &Option<Result<Answer>>,
+    ) -> Option<Verdict> {
+        let result = result.as_ref()?;
+
+        let verdict = match (self.get(day, part), result) {
+            (None, _) => Verdict::Unknown,
+            (Some(expected), Ok(answer)) if answer.matches(expected) => Verdict::Correct,
+            // an error is a regression too
+            (Some(expected), _) => Verdict::Wrong {
+                expected: expected.to_string(),
+            },
+        };
+
+        Some(verdict)
+    }
+
+    pub fn verify(&self, day: &mut DayResult) {
+        day.first_verdict = self.verdict(day.number, Part::First, &day.first_result);
+        day.second_verdict = self.verdict(day.number, Part::Second, &day.second_result);
+    }
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("# comment\n01 1 42\n\n1 2 hello world\n25 1 x").unwrap();
+        assert_eq!(answers.get(1, Part::First), Some("42"));
+        assert_eq!(answers.get(1, Part::Second), Some("hello world"));
+        assert_eq!(answers.get(25, Part::First), Some("x"));
+        assert_eq!(answers.get(25, Part::Second), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = Answers::parse("01 1 42\n01 3 42").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 2:4: expected part 1 or 2"
+        );
+        assert!(Answers::parse("x 1 42").is_err());
+        assert!(Answers::parse("01").is_err());
+        assert!(Answers::parse("01 1").is_err());
+        assert!(Answers::parse("01 1 ").is_err());
+    }
+
+    #[test]
+    fn test_verdict() {
+        let answers = Answers::parse("01 1 42").unwrap();
+        let verdict = |part, result| answers.verdict(1, part, &result);
+
+        assert_eq!(
+            verdict(Part::First, Some(Ok("42".into()))),
+            Some(Verdict::Correct)
+        );
+        let wrong = Some(Verdict::Wrong {
+            expected: "42".into(),
+        });
+        assert_eq!(verdict(Part::First, Some(Ok("41".into()))), wrong);
+        assert_eq!(verdict(Part::First, Some(Err(Error::NotFound))), wrong);
+        assert_eq!(
+            verdict(Part::Second, Some(Ok("42".into()))),
+            Some(Verdict::Unknown)
+        );
+        assert_eq!(verdict(Part::First, None), None);
+
+        let answers = Answers::parse("20 2 #.\\n.#").unwrap();
+        let picture = Answer::Picture(vec!["#.".to_string(), ".#".to_string()]);
+        assert_eq!(
+            answers.verdict(20, Part::Second, &Some(Ok(picture))),
+            Some(Verdict::Correct)
+        );
+    }
+
+    #[test]
+    fn test_answers_file() {
+        assert!(Answers::load("src/answers.txt").is_ok());
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs::{read_to_string, remove_file, write},
+    hash::{Hash, Hasher},
+    io::ErrorKind,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use num::BigInt;
+
+use crate::{
+    days::{
+        parse::{self, LineError, LineResult},
+        Answer, Part,
+    },
+    error::{Error, Result},
+};
+
+// On-disk store of the answers already found, one line per answer:
+// "<version> <day> <part> <input hash> <kind> <answer>"
+// Answers of other versions of the binary are never returned, and dropped on save.
+// It is shared by the days running in parallel, hence the mutex
+#[derive(Debug)]
+pub struct Cache {
+    path: String,
+    version: String,
+    answers: Mutex<HashMap<Key, Answer>>,
+    changed: AtomicBool,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+struct Key {
+    number: usize,
+    part: Part,
+    input: u64,
+}
+
+impl Cache {
+    // where to keep the cache instead of the default file
+    pub const FILE_VAR: &'static str = "AOC_CACHE_FILE";
+
+    // `version` identifies the binary that computed the answers, e.g. a hash of the executable,
+    // and must not contain whitespace
+    pub fn new(path: &str, version: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            version: version.to_string(),
+            answers: Mutex::new(HashMap::new()),
+            changed: AtomicBool::new(false),
+        }
+    }
+
+    // a missing file is just an empty cache
+    pub fn load(path: &str, version: &str) -> Result<Self> {
+        let content = match read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(Error::Io {
+                    path: path.to_string(),
+                    kind: e.kind(),
+                })
+            }
+        };
+
+        let cache = Self::new(path, version);
+        *cache.answers.lock().unwrap() = Self::parse(&content, version)?;
+        Ok(cache)
+    }
+
+    fn parse(s: &str, version: &str) -> Result<HashMap<Key, Answer>> {
+        let entries = parse::lines(s, 1, |line| {
+            let tokens = line.splitn(6, ' ').collect::<Vec<_>>();
+            if tokens.len() != 6 {
+                return Err(LineError::new(1, "expected 6 fields"));
+            }
+
+            if tokens[0] != version {
+                return Ok(None);
+            }
+
+            let key = Key {
+                number: parse::number(line, tokens[1])?,
+                part: parse_part(line, tokens[2])?,
+                input: u64::from_str_radix(tokens[3], 16)
+                    .map_err(|e| LineError::at(line, tokens[3], e.to_string()))?,
+            };
+
+            Ok(Some((key, parse_answer(line, tokens[4], tokens[5])?)))
+        })?;
+
+        Ok(entries.into_iter().flatten().collect())
+    }
+
+    pub fn get(&self, number: usize, part: Part, input: &str) -> Option<Answer> {
+        let key = Key::new(number, part, input);
+        self.answers.lock().unwrap().get(&key).cloned()
+    }
+
+    pub fn insert(&self, number: usize, part: Part, input: &str, answer: Answer) {
+        let key = Key::new(number, part, input);
+        self.answers.lock().unwrap().insert(key, answer);
+        self.changed.store(true, Ordering::Relaxed);
+    }
+
+    // rewrites the whole file, if anything was inserted since it was loaded
+    pub fn save(&self) -> Result<()> {
+        if !self.changed.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let answers = self.answers.lock().unwrap();
+        let mut entries = answers.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(key, _)| (key.number, part_number(key.part), key.input));
+
+        let content = entries
+            .into_iter()
+            .map(|(key, answer)| {
+                format!(
+                    "{} {} {} {:016x} {} {}\n",
+                    self.version,
+                    key.number,
+                    part_number(key.part),
+                    key.input,
+                    answer.kind(),
+                    escape(&answer.to_string())
+                )
+            })
+            .collect::<String>();
+
+        write(&self.path, content).map_err(|e| Error::Write {
+            path: self.path.clone(),
+            kind: e.kind(),
+        })
+    }
+
+    // forgets every answer, the file included
+    pub fn clear(&self) -> Result<()> {
+        self.answers.lock().unwrap().clear();
+        self.changed.store(false, Ordering::Relaxed);
+
+        match remove_file(&self.path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(Error::Write {
+                path: self.path.clone(),
+                kind: e.kind(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Key {
+    // the default hasher has fixed keys, so the same input always gets the same hash
+    fn new(number: usize, part: Part, input: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        input.hash(&mut hasher);
+
+        Self {
+            number,
+            part,
+            input: hasher.finish(),
+        }
+    }
+}
+
+fn part_number(part: Part) -> usize {
+    match part {
+        Part::First => 1,
+        Part::Second => 2,
+    }
+}
+
+fn parse_part(line: &str, token: &str) -> LineResult<Part> {
+    match token {
+        "1" => Ok(Part::First),
+        "2" => Ok(Part::Second),
+        _ => Err(LineError::at(line, token, "expected part 1 or 2")),
+    }
+}
+
+fn parse_answer(line: &str, kind: &str, s: &str) -> LineResult<Answer> {
+    let answer = match kind {
+        "integer" => Answer::Integer(parse::number(line, s)?),
+        "big_integer" => Answer::BigInteger(parse::number::<BigInt>(line, s)?),
+        "text" => Answer::Text(unescape(s)),
+        "picture" => Answer::Picture(unescape(s).split('\n').map(String::from).collect()),
+        "none" => Answer::None,
+        _ => return Err(LineError::at(line, kind, "unknown answer kind")),
+    };
+
+    Ok(answer)
+}
+
+// one answer per line, whatever it contains
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push('\\'),
+            },
+            c => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_cache() {
+        let path = env::temp_dir().join(format!("aoc2020_cache_{}", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let picture = Answer::Picture(vec!["#.\\".to_string(), ".#".to_string()]);
+        let big = Answer::from(BigInt::from(i64::MAX) * 4);
+
+        let cache = Cache::new(path, "v1");
+        cache.clear().unwrap();
+        cache.insert(1, Part::First, "1\n2", 42.into());
+        cache.insert(1, Part::Second, "1\n2", picture.clone());
+        cache.insert(2, Part::First, "x", big.clone());
+        cache.insert(3, Part::First, "y", Answer::None);
+        cache.save().unwrap();
+
+        let cache = Cache::load(path, "v1").unwrap();
+        assert_eq!(cache.get(1, Part::First, "1\n2"), Some(42.into()));
+        assert_eq!(cache.get(1, Part::Second, "1\n2"), Some(picture));
+        assert_eq!(cache.get(2, Part::First, "x"), Some(big));
+        assert_eq!(cache.get(3, Part::First, "y"), Some(Answer::None));
+        assert_eq!(cache.get(1, Part::First, "1\n3"), None);
+        assert_eq!(cache.get(2, Part::Second, "x"), None);
+
+        // another binary may give other answers
+        let cache = Cache::load(path, "v2").unwrap();
+        assert_eq!(cache.get(1, Part::First, "1\n2"), None);
+
+        cache.clear().unwrap();
+        assert_eq!(
+            Cache::load(path, "v1").unwrap().get(3, Part::First, "y"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let error = Cache::parse("v1 1 3 00ff integer 42", "v1").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 1:6: expected part 1 or 2"
+        );
+        assert!(Cache::parse("v1 1 1 00ff", "v1").is_err());
+        assert!(Cache::parse("v0 1 3 00ff integer 42", "v1")
+            .unwrap()
+            .is_empty());
+        assert_eq!(unescape(&escape("a\\nb\nc\\")), "a\\nb\nc\\");
+    }
+}
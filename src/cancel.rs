@@ -0,0 +1,75 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::error::{Error, Result};
+
+// Cooperative cancellation: there is no way to kill a thread, so long running solvers
+// call `check` once in a while and give up as soon as their day ran out of time
+
+thread_local! {
+    static TOKEN: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    // `check` on the current thread fails once the token is cancelled
+    pub fn install(&self) {
+        TOKEN.with(|token| *token.borrow_mut() = Some(self.clone()));
+    }
+}
+
+pub fn check() -> Result<()> {
+    let cancelled = TOKEN.with(|token| token.borrow().as_ref().map(CancelToken::is_cancelled));
+
+    match cancelled {
+        Some(true) => Err(Error::Timeout),
+        _ => Ok(()),
+    }
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let token = CancelToken::default();
+        let other = token.clone();
+
+        let handle = thread::spawn(move || {
+            let before = check();
+            other.install();
+            let installed = check();
+            other.cancel();
+            (before, installed, check())
+        });
+
+        let (before, installed, cancelled) = handle.join().unwrap();
+        assert_eq!(before, Ok(()));
+        assert_eq!(installed, Ok(()));
+        assert_eq!(cancelled, Err(Error::Timeout));
+        assert!(token.is_cancelled());
+
+        // other threads are not affected
+        assert_eq!(check(), Ok(()));
+    }
+}
@@ -0,0 +1,613 @@
+use std::{collections::BTreeSet, fmt, time::Duration};
+
+use aoc2020::Part;
+
+pub const USAGE: &str = "Usage:
+    aoc2020 [DAYS] [OPTIONS]    run the selected days (default: all of them)
+    aoc2020 verify [DAYS] [OPTIONS]
+                                run the selected days and check their answers
+    aoc2020 compare [DAYS] [OPTIONS]
+                                run the selected days and compare their timings with
+                                the previous run
+    aoc2020 watch DAY [OPTIONS] run the day again whenever its input, or its answers when
+                                given, change
+    aoc2020 check [DAYS] [OPTIONS]
+                                report every problem of the inputs of the selected days,
+                                without running them (only --input-dir and --input apply)
+    aoc2020 crosscheck [DAYS] [OPTIONS]
+                                run every implementation of the selected days and check
+                                that they agree (only --part, --input-dir and --input apply)
+    aoc2020 visualize DAY [OPTIONS]
+                                write the frames of the visualization of the day, for
+                                days 11, 12, 17 and 20 (only --part, --input-dir, --input
+                                and --output-dir apply)
+    aoc2020 repl DAY [OPTIONS]  load the day and explore it interactively, type help for
+                                the commands (only --input-dir and --input apply)
+    aoc2020 list                list the registered days
+
+DAYS:
+    comma separated list of days and ranges, e.g. 3-7,12
+
+OPTIONS:
+    -p, --part <1|2>            run only the first or the second challenge
+    -e, --example               run the examples of the puzzle text instead of the inputs
+                                (only --part applies)
+    -i, --input-dir <DIR>       read inputs from DIR/day_NN
+    -f, --input <FILE>          read the input from FILE, or from stdin when FILE is -
+                                (requires a single day)
+    -o, --format <FORMAT>       output format: table, json, csv, markdown
+    -b, --bench <N>             benchmark each phase over N runs
+    -w, --warmup <N>            discarded runs before benchmarking (default: 1)
+    -j, --jobs <N>              run the days in parallel on N threads
+    -t, --timeout <DURATION>    time budget of each part, e.g. 500ms or 2s
+    -T, --day-timeout <DURATION>
+                                time budget of each day, load included
+    -a, --answers <FILE>        expected answers for verify (default: src/answers.txt),
+                                or for watch (default: none)
+    -B, --baseline <TAG>        compare with the latest run tagged TAG instead
+    -r, --threshold <PERCENT>   slowdown that counts as a regression for compare
+                                (default: 10%)
+    -g, --tag <TAG>             tag the run in the history, e.g. to use it as a baseline
+    -H, --history <FILE>        timings of the previous runs (default: history.txt)
+    -N, --no-cache              run every part instead of looking up the answers found
+                                before (always the case with --bench and compare)
+    -C, --clear-cache           forget the answers found before, then run as usual
+    -d, --output-dir <DIR>      frames of visualize go to DIR/day_NN/part_N
+                                (default: visualize)
+    -h, --help                  print this message
+
+ENVIRONMENT:
+    AOC_INPUT_DIR               input directory when --input-dir is not given
+    AOC_HISTORY_FILE            history file when --history is not given
+    AOC_CACHE_FILE              cache file instead of cache.txt";
+
+pub enum Command {
+    Run(Box<RunArgs>),
+    List,
+    Help,
+}
+
+#[derive(Default)]
+pub struct RunArgs {
+    pub days: Option<Vec<usize>>,
+    pub part: Option<Part>,
+    pub input_dir: Option<String>,
+    pub input_file: Option<String>,
+    pub format: Format,
+    pub bench: Option<usize>,
+    pub warmup: Option<usize>,
+    pub jobs: Option<usize>,
+    pub part_timeout: Option<Duration>,
+    pub day_timeout: Option<Duration>,
+    pub verify: bool,
+    pub answers_file: Option<String>,
+    pub example: bool,
+    pub compare: bool,
+    pub watch: bool,
+    pub check: bool,
+    pub cross_check: bool,
+    pub visualize: bool,
+    pub repl: bool,
+    pub output_dir: Option<String>,
+    pub baseline: Option<String>,
+    pub threshold: Option<f64>,
+    pub tag: Option<String>,
+    pub history_file: Option<String>,
+    pub no_cache: bool,
+    pub clear_cache: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CliError(String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+type CliResult<T> = Result<T, CliError>;
+
+pub fn parse_args<I>(args: I) -> CliResult<Command>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+
+    if args.peek().map(String::as_str) == Some("list") {
+        args.next();
+        return match args.next() {
+            None => Ok(Command::List),
+            Some(arg) => Err(unexpected(&arg)),
+        };
+    }
+
+    let mut run = RunArgs::default();
+
+    match args.peek().map(String::as_str) {
+        Some("verify") => run.verify = true,
+        Some("compare") => run.compare = true,
+        Some("watch") => run.watch = true,
+        Some("check") => run.check = true,
+        Some("crosscheck") => run.cross_check = true,
+        Some("visualize") => run.visualize = true,
+        Some("repl") => run.repl = true,
+        _ => (),
+    }
+
+    let command = run.verify || run.compare || run.watch || run.check || run.cross_check;
+    if command || run.visualize || run.repl {
+        args.next();
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-p" | "--part" => run.part = Some(parse_part(&value(&arg, args.next())?)?),
+            "-i" | "--input-dir" => run.input_dir = Some(value(&arg, args.next())?),
+            "-f" | "--input" => run.input_file = Some(value(&arg, args.next())?),
+            "-o" | "--format" => run.format = parse_format(&value(&arg, args.next())?)?,
+            "-b" | "--bench" => run.bench = Some(parse_count(&value(&arg, args.next())?, 1)?),
+            "-w" | "--warmup" => run.warmup = Some(parse_count(&value(&arg, args.next())?, 0)?),
+            "-j" | "--jobs" => run.jobs = Some(parse_count(&value(&arg, args.next())?, 1)?),
+            "-t" | "--timeout" => {
+                run.part_timeout = Some(parse_duration(&value(&arg, args.next())?)?)
+            }
+            "-T" | "--day-timeout" => {
+                run.day_timeout = Some(parse_duration(&value(&arg, args.next())?)?)
+            }
+            "-a" | "--answers" => run.answers_file = Some(value(&arg, args.next())?),
+            "-e" | "--example" => run.example = true,
+            "-B" | "--baseline" => run.baseline = Some(parse_tag(&value(&arg, args.next())?)?),
+            "-r" | "--threshold" => {
+                run.threshold = Some(parse_percent(&value(&arg, args.next())?)?)
+            }
+            "-g" | "--tag" => run.tag = Some(parse_tag(&value(&arg, args.next())?)?),
+            "-H" | "--history" => run.history_file = Some(value(&arg, args.next())?),
+            "-N" | "--no-cache" => run.no_cache = true,
+            "-C" | "--clear-cache" => run.clear_cache = true,
+            "-d" | "--output-dir" => run.output_dir = Some(value(&arg, args.next())?),
+            flag if flag.starts_with('-') => {
+                return Err(CliError(format!("unknown option '{}'", flag)))
+            }
+            days if run.days.is_none() => run.days = Some(parse_days(days)?),
+            _ => return Err(unexpected(&arg)),
+        }
+    }
+
+    let single_day = run.days.as_ref().map(|days| days.len()) == Some(1);
+    if run.input_file.is_some() && !single_day {
+        return Err(CliError("--input requires exactly one day".to_string()));
+    }
+
+    if run.warmup.is_some() && run.bench.is_none() {
+        return Err(CliError("--warmup requires --bench".to_string()));
+    }
+
+    if run.answers_file.is_some() && !(run.verify || run.watch) {
+        return Err(CliError("--answers requires verify or watch".to_string()));
+    }
+
+    if run.watch && !watch_options(&run, single_day) {
+        return Err(CliError(
+            "watch requires exactly one day with an input file, in the table format".to_string(),
+        ));
+    }
+
+    if (run.baseline.is_some() || run.threshold.is_some()) && !run.compare {
+        return Err(CliError(
+            "--baseline and --threshold require compare".to_string(),
+        ));
+    }
+
+    if run.compare && run.format != Format::Table {
+        return Err(CliError(
+            "compare supports only the table format".to_string(),
+        ));
+    }
+
+    if run.example && !only_part(&run) {
+        return Err(CliError("--example only supports --part".to_string()));
+    }
+
+    if run.check && !(run.part.is_none() && only_inputs(&run)) {
+        return Err(CliError(
+            "check only supports --input-dir and --input".to_string(),
+        ));
+    }
+
+    if run.cross_check && !only_inputs(&run) {
+        return Err(CliError(
+            "crosscheck only supports --part, --input-dir and --input".to_string(),
+        ));
+    }
+
+    if run.visualize && !(single_day && only_inputs(&run)) {
+        return Err(CliError(
+            "visualize requires exactly one day and only supports --part, --input-dir, --input \
+             and --output-dir"
+                .to_string(),
+        ));
+    }
+
+    // stdin is where the commands come from
+    let repl_options = single_day && run.part.is_none() && run.input_file.as_deref() != Some("-");
+    if run.repl && !(repl_options && only_inputs(&run)) {
+        return Err(CliError(
+            "repl requires exactly one day and only supports --input-dir and --input from a file"
+                .to_string(),
+        ));
+    }
+
+    if run.output_dir.is_some() && !run.visualize {
+        return Err(CliError("--output-dir requires visualize".to_string()));
+    }
+
+    Ok(Command::Run(Box::new(run)))
+}
+
+// runs are not recorded, there would be one for each save
+fn watch_options(run: &RunArgs, single_day: bool) -> bool {
+    single_day
+        && run.input_file.as_deref() != Some("-")
+        && run.format == Format::Table
+        && run.tag.is_none()
+}
+
+// examples have their own inputs and answers, and are quick enough to run as they are
+fn only_part(run: &RunArgs) -> bool {
+    run.input_dir.is_none()
+        && run.input_file.is_none()
+        && run.format == Format::Table
+        && run.bench.is_none()
+        && run.jobs.is_none()
+        && run.part_timeout.is_none()
+        && run.day_timeout.is_none()
+        && !run.verify
+        && !run.compare
+        && !run.watch
+        && run.tag.is_none()
+        && run.history_file.is_none()
+        && !run.no_cache
+        && !run.clear_cache
+}
+
+// besides the part, only where the inputs come from matters
+fn only_inputs(run: &RunArgs) -> bool {
+    run.format == Format::Table
+        && run.bench.is_none()
+        && run.jobs.is_none()
+        && run.part_timeout.is_none()
+        && run.day_timeout.is_none()
+        && !run.example
+        && run.tag.is_none()
+        && run.history_file.is_none()
+        && !run.no_cache
+        && !run.clear_cache
+}
+
+fn value(flag: &str, value: Option<String>) -> CliResult<String> {
+    value.ok_or_else(|| CliError(format!("missing value for '{}'", flag)))
+}
+
+fn unexpected(arg: &str) -> CliError {
+    CliError(format!("unexpected argument '{}'", arg))
+}
+
+// "3-7,12" -> [3, 4, 5, 6, 7, 12]
+fn parse_days(s: &str) -> CliResult<Vec<usize>> {
+    let mut days = BTreeSet::new();
+
+    for item in s.split(',') {
+        let (first, last) = match item.split_once('-') {
+            Some((first, last)) => (parse_day(first)?, parse_day(last)?),
+            None => (parse_day(item)?, parse_day(item)?),
+        };
+
+        if first > last {
+            return Err(CliError(format!("invalid range '{}'", item)));
+        }
+
+        days.extend(first..=last);
+    }
+
+    Ok(days.into_iter().collect())
+}
+
+fn parse_day(s: &str) -> CliResult<usize> {
+    s.parse::<usize>()
+        .ok()
+        .filter(|&day| day > 0)
+        .ok_or_else(|| CliError(format!("invalid day '{}'", s)))
+}
+
+fn parse_part(s: &str) -> CliResult<Part> {
+    match s {
+        "1" => Ok(Part::First),
+        "2" => Ok(Part::Second),
+        _ => Err(CliError(format!("invalid part '{}' (expected 1 or 2)", s))),
+    }
+}
+
+fn parse_count(s: &str, min: usize) -> CliResult<usize> {
+    s.parse::<usize>()
+        .ok()
+        .filter(|&count| count >= min)
+        .ok_or_else(|| CliError(format!("invalid count '{}'", s)))
+}
+
+// "500ms", "2s" or "1.5" seconds
+fn parse_duration(s: &str) -> CliResult<Duration> {
+    let duration = match s.strip_suffix("ms") {
+        Some(millis) => millis.parse::<u64>().ok().map(Duration::from_millis),
+        None => s
+            .strip_suffix('s')
+            .unwrap_or(s)
+            .parse::<f64>()
+            .ok()
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            .map(Duration::from_secs_f64),
+    };
+
+    duration
+        .filter(|duration| !duration.is_zero())
+        .ok_or_else(|| CliError(format!("invalid duration '{}'", s)))
+}
+
+// "10%" or "10", as a fraction
+fn parse_percent(s: &str) -> CliResult<f64> {
+    s.strip_suffix('%')
+        .unwrap_or(s)
+        .parse::<f64>()
+        .ok()
+        .filter(|percent| percent.is_finite() && *percent >= 0.0)
+        .map(|percent| percent / 100.0)
+        .ok_or_else(|| CliError(format!("invalid percentage '{}'", s)))
+}
+
+// tags are stored as a single word, "-" means no tag
+fn parse_tag(s: &str) -> CliResult<String> {
+    if s.is_empty() || s == "-" || s.contains(char::is_whitespace) {
+        return Err(CliError(format!("invalid tag '{}'", s)));
+    }
+
+    Ok(s.to_string())
+}
+
+fn parse_format(s: &str) -> CliResult<Format> {
+    match s {
+        "table" => Ok(Format::Table),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        "markdown" | "md" => Ok(Format::Markdown),
+        _ => Err(CliError(format!("unknown format '{}'", s))),
+    }
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> CliResult<Command> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    fn parse_run(args: &str) -> RunArgs {
+        match parse(args) {
+            Ok(Command::Run(run)) => *run,
+            _ => panic!("expected run command"),
+        }
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(parse_run("").days, None);
+        assert_eq!(parse_run("5").days, Some(vec![5]));
+        assert_eq!(parse_run("3-7,12").days, Some(vec![3, 4, 5, 6, 7, 12]));
+        assert_eq!(parse_run("12,1-2,2").days, Some(vec![1, 2, 12]));
+    }
+
+    #[test]
+    fn test_invalid_days() {
+        assert!(parse("7-3").is_err());
+        assert!(parse("0").is_err());
+        assert!(parse("1,,2").is_err());
+        assert!(parse("1 2").is_err());
+    }
+
+    #[test]
+    fn test_options() {
+        let run = parse_run("3 --part 2 -i inputs -f day_03.txt -o table");
+        assert_eq!(run.part, Some(Part::Second));
+        assert_eq!(run.input_dir.as_deref(), Some("inputs"));
+        assert_eq!(run.input_file.as_deref(), Some("day_03.txt"));
+        assert_eq!(run.format, Format::Table);
+        assert_eq!(run.jobs, None);
+        assert_eq!(parse_run("-j 4").jobs, Some(4));
+        assert!(parse("--jobs 0").is_err());
+        assert_eq!(parse_run("-o json").format, Format::Json);
+        assert_eq!(parse_run("-o csv").format, Format::Csv);
+        assert_eq!(parse_run("--format md").format, Format::Markdown);
+    }
+
+    #[test]
+    fn test_bench() {
+        let run = parse_run("--bench 10 -w 0");
+        assert_eq!(run.bench, Some(10));
+        assert_eq!(run.warmup, Some(0));
+        assert_eq!(parse_run("-b 5").warmup, None);
+        assert!(parse("--bench 0").is_err());
+        assert!(parse("--warmup 3").is_err());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let run = parse_run("-t 500ms --day-timeout 2s");
+        assert_eq!(run.part_timeout, Some(Duration::from_millis(500)));
+        assert_eq!(run.day_timeout, Some(Duration::from_secs(2)));
+        assert_eq!(
+            parse_run("--timeout 1.5").part_timeout,
+            Some(Duration::from_millis(1500))
+        );
+        assert!(parse("-t 0s").is_err());
+        assert!(parse("-t 1.5ms").is_err());
+        assert!(parse("-t -1").is_err());
+        assert!(parse("-t soon").is_err());
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        assert_eq!(
+            parse("--wat").err(),
+            Some(CliError("unknown option '--wat'".to_string()))
+        );
+        assert!(parse("--part 3").is_err());
+        assert!(parse("--part").is_err());
+        assert!(parse("--format xml").is_err());
+        assert!(parse("1-3 --input day.txt").is_err());
+    }
+
+    #[test]
+    fn test_commands() {
+        assert!(matches!(parse("list"), Ok(Command::List)));
+        assert!(parse("list 3").is_err());
+        assert!(matches!(parse("3 --help"), Ok(Command::Help)));
+
+        let run = parse_run("verify 1-3 -a answers.txt");
+        assert!(run.verify);
+        assert_eq!(run.days, Some(vec![1, 2, 3]));
+        assert_eq!(run.answers_file.as_deref(), Some("answers.txt"));
+        assert!(!parse_run("1-3").verify);
+        assert!(parse("1-3 verify").is_err());
+        assert!(parse("-a answers.txt").is_err());
+    }
+
+    #[test]
+    fn test_example() {
+        let run = parse_run("1-3 --example -p 1");
+        assert!(run.example);
+        assert_eq!(run.part, Some(Part::First));
+        assert!(!parse_run("1-3").example);
+        assert!(parse("-e -o json").is_err());
+        assert!(parse("-e -j 2").is_err());
+        assert!(parse("verify -e").is_err());
+        assert!(parse("3 -e -f day_03.txt").is_err());
+        assert!(parse("-e -g fast").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let run = parse_run("compare 1-3 -B fast --threshold 25% -H runs.txt");
+        assert!(run.compare);
+        assert!(!run.verify);
+        assert_eq!(run.days, Some(vec![1, 2, 3]));
+        assert_eq!(run.baseline.as_deref(), Some("fast"));
+        assert_eq!(run.threshold, Some(0.25));
+        assert_eq!(run.history_file.as_deref(), Some("runs.txt"));
+        assert_eq!(parse_run("compare -r 5").threshold, Some(0.05));
+        assert_eq!(parse_run("--tag fast").tag.as_deref(), Some("fast"));
+        assert!(parse("-B fast").is_err());
+        assert!(parse("-r 10").is_err());
+        assert!(parse("compare -r -1").is_err());
+        assert!(parse("compare -o json").is_err());
+        assert!(parse("--tag -").is_err());
+        assert!(parse("compare verify").is_err());
+    }
+
+    #[test]
+    fn test_watch() {
+        let run = parse_run("watch 3 -f day_03.txt -a answers.txt");
+        assert!(run.watch);
+        assert_eq!(run.days, Some(vec![3]));
+        assert_eq!(run.answers_file.as_deref(), Some("answers.txt"));
+        assert!(parse("watch").is_err());
+        assert!(parse("watch 1-3").is_err());
+        assert!(parse("watch 3 -f -").is_err());
+        assert!(parse("watch 3 -o csv").is_err());
+        assert!(parse("watch 3 -e").is_err());
+        assert!(parse("watch 3 --tag fast").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let run = parse_run("check 3-5 -i inputs");
+        assert!(run.check);
+        assert!(!run.verify);
+        assert_eq!(run.days, Some(vec![3, 4, 5]));
+        assert_eq!(run.input_dir.as_deref(), Some("inputs"));
+        assert!(parse_run("check 3 -f -").check);
+        assert!(parse("check -p 1").is_err());
+        assert!(parse("check -e").is_err());
+        assert!(parse("check -o json").is_err());
+        assert!(parse("check -b 3").is_err());
+        assert!(parse("check verify").is_err());
+        assert!(parse("check -N").is_err());
+    }
+
+    #[test]
+    fn test_cross_check() {
+        let run = parse_run("crosscheck 13 -p 2 -f day.txt");
+        assert!(run.cross_check);
+        assert!(!run.check);
+        assert_eq!(run.days, Some(vec![13]));
+        assert_eq!(parse_run("crosscheck 13,15").days, Some(vec![13, 15]));
+        assert_eq!(run.part, Some(Part::Second));
+        assert!(parse("crosscheck -b 5").is_err());
+        assert!(parse("crosscheck -e").is_err());
+        assert!(parse("crosscheck -o csv").is_err());
+        assert!(parse("crosscheck check").is_err());
+    }
+
+    #[test]
+    fn test_visualize() {
+        let run = parse_run("visualize 11 -p 2 -f day_11.txt -d frames");
+        assert!(run.visualize);
+        assert_eq!(run.days, Some(vec![11]));
+        assert_eq!(run.part, Some(Part::Second));
+        assert_eq!(run.output_dir.as_deref(), Some("frames"));
+        assert_eq!(parse_run("visualize 20").output_dir, None);
+        assert!(parse("visualize").is_err());
+        assert!(parse("visualize 11-12").is_err());
+        assert!(parse_run("visualize 11 -f -").visualize);
+        assert!(parse("visualize 11 -b 3").is_err());
+        assert!(parse("visualize 11 -e").is_err());
+        assert!(parse("11 -d frames").is_err());
+    }
+
+    #[test]
+    fn test_repl() {
+        let run = parse_run("repl 7 -f day_07.txt");
+        assert!(run.repl);
+        assert!(!run.visualize);
+        assert_eq!(run.days, Some(vec![7]));
+        assert!(parse_run("repl 15 -i inputs").repl);
+        assert!(parse("repl").is_err());
+        assert!(parse("repl 7-8").is_err());
+        assert!(parse("repl 7 -f -").is_err());
+        assert!(parse("repl 7 -p 1").is_err());
+        assert!(parse("repl 7 -o json").is_err());
+        assert!(parse("repl 7 -d frames").is_err());
+    }
+
+    #[test]
+    fn test_cache() {
+        let run = parse_run("1-3 --no-cache");
+        assert!(run.no_cache);
+        assert!(!run.clear_cache);
+        assert!(parse_run("verify -C").clear_cache);
+        assert!(!parse_run("1-3").no_cache);
+        assert!(parse("-e --clear-cache").is_err());
+        assert!(parse("-e -N").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    days::{self, Advent, Answer, Implementation, Part},
+    error::Result,
+};
+
+pub struct ImplementationResult {
+    pub name: &'static str,
+    pub result: Result<Answer>,
+    pub elapsed: Duration,
+}
+
+// the default implementation always comes first
+pub struct CrossCheck {
+    pub number: usize,
+    pub part: Part,
+    pub results: Vec<ImplementationResult>,
+}
+
+impl CrossCheck {
+    // same answer for everyone, or the same error
+    pub fn agrees(&self) -> bool {
+        self.results
+            .windows(2)
+            .all(|pair| pair[0].result == pair[1].result)
+    }
+}
+
+// Runs every implementation of the selected days on the same input, loaded once.
+// Only parts with alternative implementations are checked, unknown days are skipped
+pub fn cross_check(advent: &Advent, day_numbers: &[usize], part: Option<Part>) -> Vec<CrossCheck> {
+    let entries = day_numbers.iter().filter_map(|&number| days::entry(number));
+    let mut checks = Vec::new();
+
+    for entry in entries {
+        let parts = [Part::First, Part::Second]
+            .iter()
+            .copied()
+            .filter(|&p| part.map(|part| part == p).unwrap_or(true))
+            .filter(|&p| entry.implementations.iter().any(|i| i.part == p))
+            .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            continue;
+        }
+
+        let day = advent.load_day(entry.number);
+
+        for part in parts {
+            let alternatives = entry.implementations.iter().filter(|i| i.part == part);
+            let implementations = std::iter::once((Implementation::DEFAULT, None))
+                .chain(alternatives.map(|i| (i.name, Some(i.solve))));
+
+            // a day that fails to load fails with every implementation
+            let results = implementations
+                .map(|(name, solve)| {
+                    let clock = Instant::now();
+                    let result = match (&day, solve) {
+                        (Ok(day), Some(solve)) => solve(day.as_ref()),
+                        (Ok(day), None) => part.solve(day.as_ref()),
+                        (Err(error), _) => Err(error.clone()),
+                    };
+
+                    ImplementationResult {
+                        name,
+                        result,
+                        elapsed: clock.elapsed(),
+                    }
+                })
+                .collect();
+
+            checks.push(CrossCheck {
+                number: entry.number,
+                part,
+                results,
+            });
+        }
+    }
+
+    checks
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{days::DAY_NUMBERS, error::Error};
+
+    // the quick examples are enough to catch an implementation gone astray
+    #[test]
+    fn test_implementations() {
+        for &number in DAY_NUMBERS.iter() {
+            let entry = days::entry(number).unwrap();
+            for implementation in entry.implementations.iter() {
+                let examples = entry
+                    .examples
+                    .iter()
+                    .filter(|example| example.part == implementation.part && !example.slow);
+
+                for example in examples {
+                    let day = (entry.load)(example.input).unwrap();
+                    let answer = (implementation.solve)(day.as_ref()).unwrap();
+                    assert!(
+                        answer.matches(example.expected),
+                        "day {} {}",
+                        number,
+                        implementation.name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cross_check() {
+        let advent = Advent::new("src/input".to_string()).with_input(13, "x".to_string());
+        let checks = cross_check(&advent, &[2, 1, 13, 42], None);
+
+        // day 2 has no alternatives, day 1 only for the second part
+        let parts = checks
+            .iter()
+            .map(|check| (check.number, check.part, check.results.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(parts, vec![(1, Part::Second, 2), (13, Part::Second, 2)]);
+
+        assert!(checks[0].agrees());
+        assert_eq!(checks[0].results[0].name, Implementation::DEFAULT);
+        assert!(matches!(checks[1].results[1].result, Err(Error::Parse(_))));
+
+        let mut check = cross_check(&advent, &[1], Some(Part::Second)).remove(0);
+        check.results[1].result = Ok(42.into());
+        assert!(!check.agrees());
+        assert!(cross_check(&advent, &[1], Some(Part::First)).is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::{cancel, error::Result};
+
+// Cellular automata on an infinite grid of any shape, e.g. the cubes of day 17 or the tiles
+// of day 24. Only the active cells are stored: whatever the grid, a cell is a coordinate and
+// its neighbours are whatever `neighbours` returns for it
+
+// how many active neighbours each cell has, cells with none are left out
+pub fn count_neighbours<C, F, I>(active: &HashSet<C>, neighbours: F) -> HashMap<C, usize>
+where
+    C: Copy + Eq + Hash,
+    F: Fn(C) -> I,
+    I: IntoIterator<Item = C>,
+{
+    let mut counts = HashMap::new();
+    for &cell in active.iter() {
+        for neighbour in neighbours(cell) {
+            *counts.entry(neighbour).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+// the next generation: `rule(is_active, active_neighbours)` tells whether a cell is active
+// afterwards. Inactive cells without active neighbours are not even asked, they stay inactive.
+// Fails once the day runs out of time, the grid may keep growing for as many generations
+pub fn step<C, F, I, R>(active: &HashSet<C>, neighbours: F, rule: R) -> Result<HashSet<C>>
+where
+    C: Copy + Eq + Hash,
+    F: Fn(C) -> I,
+    I: IntoIterator<Item = C>,
+    R: Fn(bool, usize) -> bool,
+{
+    cancel::check()?;
+    let counts = count_neighbours(active, neighbours);
+
+    let lonely = active
+        .iter()
+        .filter(|cell| !counts.contains_key(cell))
+        .filter(|_| rule(true, 0))
+        .copied();
+
+    Ok(counts
+        .iter()
+        .filter(|&(cell, &count)| rule(active.contains(cell), count))
+        .map(|(&cell, _)| cell)
+        .chain(lonely)
+        .collect())
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn neighbours((x, y): (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+        (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+            .filter(|&d| d != (0, 0))
+            .map(move |(dx, dy)| (x + dx, y + dy))
+    }
+
+    fn life(active: bool, neighbours: usize) -> bool {
+        matches!((active, neighbours), (true, 2) | (_, 3))
+    }
+
+    #[test]
+    fn test_step() {
+        let blinker = [(0, -1), (0, 0), (0, 1)].iter().copied().collect();
+        let counts = count_neighbours(&blinker, neighbours);
+        assert_eq!(
+            (counts[&(0, 0)], counts[&(1, 0)], counts.get(&(3, 0))),
+            (2, 3, None)
+        );
+
+        let turned = step(&blinker, neighbours, life).unwrap();
+        assert_eq!(turned, [(-1, 0), (0, 0), (1, 0)].iter().copied().collect());
+        assert_eq!(step(&turned, neighbours, life), Ok(blinker));
+
+        let lonely = [(5, 5)].iter().copied().collect();
+        assert!(step(&lonely, neighbours, life).unwrap().is_empty());
+        assert_eq!(step(&lonely, neighbours, |active, _| active), Ok(lonely));
+    }
+}
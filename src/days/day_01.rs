@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 
-use super::Day;
+use super::{downcast, parse, Answer, Day, Example, Implementation};
+use crate::error::{Error, Result};
+
+register_day!(1, "Report Repair", Day01, EXAMPLES, IMPLEMENTATIONS);
 
 const TARGET: i32 = 2020;
 
@@ -9,46 +12,95 @@ pub struct Day01 {
 }
 
 impl Day01 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Vec<i32> {
-        s.lines()
-            .map(|line| line.parse::<i32>())
-            .collect::<Result<_, _>>()
-            .expect("Parse input failed")
+    fn parse_input(s: &str) -> Result<Vec<i32>> {
+        parse::lines(s, 1, |line| parse::number(line, line))
+    }
+
+    // O(N^2) - ...like this one: fix the smallest and squeeze the other two from both ends
+    fn two_pointers(&self) -> Result<Answer> {
+        let mut sorted = self.input.clone();
+        sorted.sort_unstable();
+
+        for (i, &x) in sorted.iter().enumerate() {
+            let (mut j, mut k) = (i + 1, sorted.len().saturating_sub(1));
+            while j < k {
+                match x + sorted[j] + sorted[k] {
+                    sum if sum < TARGET => j += 1,
+                    sum if sum > TARGET => k -= 1,
+                    _ => return Ok((x * sorted[j] * sorted[k]).into()),
+                }
+            }
+        }
+
+        Err(Error::NotFound)
     }
 }
 
 impl Day for Day01 {
     // O(N)
-    fn first_challenge(&self) -> String {
+    fn first_challenge(&self) -> Result<Answer> {
         let mut seen = HashSet::new();
         for &x in self.input.iter() {
             if seen.contains(&(TARGET - x)) {
-                return (x * (TARGET - x)).to_string();
+                return Ok((x * (TARGET - x)).into());
             }
             seen.insert(x);
         }
 
-        "NOT FOUND".to_string()
+        Err(Error::NotFound)
     }
 
     // O(N^3) - Look for better solutions elsewhere...
-    fn second_challenge(&self) -> String {
+    fn second_challenge(&self) -> Result<Answer> {
         for i in 0..self.input.len() {
             for j in (i + 1)..self.input.len() {
                 for k in (j + 1)..self.input.len() {
                     if self.input[i] + self.input[j] + self.input[k] == TARGET {
-                        return (self.input[i] * self.input[j] * self.input[k]).to_string();
+                        return Ok((self.input[i] * self.input[j] * self.input[k]).into());
                     }
                 }
             }
         }
 
-        "NOT FOUND".to_string()
+        Err(Error::NotFound)
+    }
+}
+
+/* examples */
+
+const INPUT: &str = "1721
+979
+366
+299
+675
+1456";
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "514579"),
+    Example::second(INPUT, "241861950"),
+];
+
+/* implementations */
+
+const IMPLEMENTATIONS: &[Implementation] = &[Implementation::second("two pointers", |day| {
+    downcast::<Day01>(day).two_pointers()
+})];
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_found() {
+        let day = Day01::load("1\n2").unwrap();
+        assert_eq!(day.first_challenge(), Err(Error::NotFound));
     }
 }
@@ -1,23 +1,24 @@
-use std::{error::Error, str::FromStr};
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::Result;
 
-use super::Day;
+register_day!(2, "Password Philosophy", Day02, EXAMPLES);
 
 pub struct Day02 {
     input: Vec<Entry>,
 }
 
 impl Day02 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Vec<Entry> {
-        s.lines()
-            .map(Entry::from_str)
-            .collect::<Result<_, _>>()
-            .expect("Parse input failed")
+    fn parse_input(s: &str) -> Result<Vec<Entry>> {
+        parse::lines(s, 1, Entry::parse)
     }
 
     fn count_valid<V>(&self) -> usize
@@ -32,12 +33,12 @@ impl Day02 {
 }
 
 impl Day for Day02 {
-    fn first_challenge(&self) -> String {
-        self.count_valid::<OldValidator>().to_string()
+    fn first_challenge(&self) -> Result<Answer> {
+        Ok(self.count_valid::<OldValidator>().into())
     }
 
-    fn second_challenge(&self) -> String {
-        self.count_valid::<NewValidator>().to_string()
+    fn second_challenge(&self) -> Result<Answer> {
+        Ok(self.count_valid::<NewValidator>().into())
     }
 }
 
@@ -46,15 +47,13 @@ struct Entry {
     policy: Policy,
 }
 
-impl FromStr for Entry {
-    type Err = Box<dyn Error>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vec = s.split(": ").collect::<Vec<_>>();
+impl Entry {
+    fn parse(s: &str) -> LineResult<Self> {
+        let (policy, password) = parse::split_pair(s, s, ": ")?;
 
         Ok(Self {
-            policy: vec[0].parse()?,
-            password: vec[1].trim().to_string(),
+            policy: Policy::parse(s, policy)?,
+            password: password.trim().to_string(),
         })
     }
 }
@@ -65,16 +64,19 @@ struct Policy {
     second: usize,
 }
 
-impl FromStr for Policy {
-    type Err = Box<dyn Error>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vec = s.split(|c| c == '-' || c == ' ').collect::<Vec<_>>();
+impl Policy {
+    // `s` is a slice of `line`
+    fn parse(line: &str, s: &str) -> LineResult<Self> {
+        let (range, letter) = parse::split_pair(line, s, " ")?;
+        let (first, second) = parse::split_pair(line, range, "-")?;
 
         Ok(Self {
-            first: vec[0].parse::<usize>()?,
-            second: vec[1].parse::<usize>()?,
-            letter: vec[2].chars().next().ok_or("Letter not found")?,
+            first: parse::number(line, first)?,
+            second: parse::number(line, second)?,
+            letter: letter
+                .chars()
+                .next()
+                .ok_or_else(|| LineError::at(line, letter, "letter not found"))?,
         })
     }
 }
@@ -95,8 +97,41 @@ impl Validator for OldValidator {
 
 impl Validator for NewValidator {
     fn is_valid(policy: &Policy, password: &str) -> bool {
-        let chars = password.as_bytes();
-        let target = policy.letter as u8;
-        (chars[policy.first - 1] == target) ^ (chars[policy.second - 1] == target)
+        let chars = password.chars().collect::<Vec<_>>();
+        let is_target = |position: usize| {
+            position
+                .checked_sub(1)
+                .and_then(|i| chars.get(i))
+                .map(|&c| c == policy.letter)
+                .unwrap_or_default()
+        };
+        is_target(policy.first) ^ is_target(policy.second)
+    }
+}
+
+/* examples */
+
+const INPUT: &str = "1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "2"),
+    Example::second(INPUT, "1"),
+];
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_policy() {
+        let error = Day02::load("1-3 a: abcde\n1-x b: cdefg").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 2:3: invalid number 'x': invalid digit found in string"
+        );
     }
 }
@@ -1,20 +1,21 @@
-use super::Day;
+use super::{parse, Answer, Day, Example};
+use crate::error::Result;
+
+register_day!(3, "Toboggan Trajectory", Day03, EXAMPLES);
 
 pub struct Day03 {
     input: Area,
 }
 
 impl Day03 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Area {
-        s.lines()
-            .map(|line| line.chars().map(Square::from).collect())
-            .collect()
+    fn parse_input(s: &str) -> Result<Area> {
+        parse::grid(s, 1, Square::parse)
     }
 
     fn count_trees(&self, right: usize, down: usize) -> usize {
@@ -30,16 +31,17 @@ impl Day03 {
 }
 
 impl Day for Day03 {
-    fn first_challenge(&self) -> String {
-        self.count_trees(3, 1).to_string()
+    fn first_challenge(&self) -> Result<Answer> {
+        Ok(self.count_trees(3, 1).into())
     }
 
-    fn second_challenge(&self) -> String {
-        [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+    fn second_challenge(&self) -> Result<Answer> {
+        let trees = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
             .iter()
             .map(|&(right, down)| self.count_trees(right, down))
-            .fold(1, std::ops::Mul::mul)
-            .to_string()
+            .product::<usize>();
+
+        Ok(trees.into())
     }
 }
 
@@ -51,23 +53,19 @@ enum Square {
     Tree,
 }
 
-impl From<char> for Square {
-    fn from(c: char) -> Self {
+impl Square {
+    fn parse(c: char) -> Option<Self> {
         match c {
-            '.' => Square::Open,
-            '#' => Square::Tree,
-            _ => panic!("Invalid square"),
+            '.' => Some(Square::Open),
+            '#' => Some(Square::Tree),
+            _ => None,
         }
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    const INPUT: &str = "..##.......
+const INPUT: &str = "..##.......
 #...#...#..
 .#....#..#.
 ..#.#...#.#
@@ -79,15 +77,7 @@ mod tests {
 #...##....#
 .#..#...#.#";
 
-    #[test]
-    fn test_first_challenge() {
-        let day = Day03::load(INPUT);
-        assert_eq!(day.first_challenge(), "7");
-    }
-
-    #[test]
-    fn test_second_challenge() {
-        let day = Day03::load(INPUT);
-        assert_eq!(day.second_challenge(), "336");
-    }
-}
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "7"),
+    Example::second(INPUT, "336"),
+];
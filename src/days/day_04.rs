@@ -1,6 +1,9 @@
 use regex::Regex;
 
-use super::Day;
+use super::{parse, Answer, Day, Example};
+use crate::error::Result;
+
+register_day!(4, "Passport Processing", Day04, EXAMPLES);
 
 pub struct Day04 {
     input: Vec<Passport>,
@@ -11,28 +14,33 @@ type Passport = Vec<PassportField>;
 type PassportField = (String, String);
 
 impl Day04 {
-    const BLANK_LINE: &'static str = "\n\n";
     const SEPARATOR: &'static str = ":";
 
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Vec<Passport> {
-        s.split(Self::BLANK_LINE)
-            .map(Self::parse_passport)
-            .collect()
+    fn parse_input(s: &str) -> Result<Vec<Passport>> {
+        parse::all(
+            parse::sections(s)
+                .into_iter()
+                .map(|(first_line, section)| Self::parse_passport(section, first_line)),
+        )
     }
 
-    fn parse_passport(s: &str) -> Passport {
-        s.split_ascii_whitespace()
-            .map(|entry| {
-                let pair = entry.split(Self::SEPARATOR).collect::<Vec<_>>();
-                (pair[0].to_string(), pair[1].to_string())
-            })
-            .collect()
+    fn parse_passport(s: &str, first_line: usize) -> Result<Passport> {
+        let lines = parse::lines(s, first_line, |line| {
+            line.split_ascii_whitespace()
+                .map(|entry| {
+                    let (key, value) = parse::split_pair(line, entry, Self::SEPARATOR)?;
+                    Ok((key.to_string(), value.to_string()))
+                })
+                .collect::<parse::LineResult<Vec<_>>>()
+        })?;
+
+        Ok(lines.into_iter().flatten().collect())
     }
 
     fn count_valid<V>(&self) -> usize
@@ -47,12 +55,12 @@ impl Day04 {
 }
 
 impl Day for Day04 {
-    fn first_challenge(&self) -> String {
-        self.count_valid::<OldValidator>().to_string()
+    fn first_challenge(&self) -> Result<Answer> {
+        Ok(self.count_valid::<OldValidator>().into())
     }
 
-    fn second_challenge(&self) -> String {
-        self.count_valid::<NewValidator>().to_string()
+    fn second_challenge(&self) -> Result<Answer> {
+        Ok(self.count_valid::<NewValidator>().into())
     }
 }
 trait PassportValidator {
@@ -151,12 +159,11 @@ impl FieldValidator for HeightValidator {
 
         HEIGHT_REGEX
             .captures(field)
-            .map(|c| {
-                let number = c.name("num").map(|m| m.as_str().parse::<i32>().unwrap());
+            .and_then(|c| {
+                let number = c.name("num").and_then(|m| m.as_str().parse::<i32>().ok());
                 let unit = c.name("unit").map(|m| m.as_str());
                 number.zip(unit)
             })
-            .flatten()
             .map(|(number, unit)| {
                 let range = match unit {
                     "cm" => 150..=193,
@@ -219,13 +226,9 @@ impl FieldValidator for PassportIdValidator {
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    const INPUT: &str = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+const INPUT_1: &str = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
 byr:1937 iyr:2017 cid:147 hgt:183cm
 
 iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
@@ -239,15 +242,7 @@ hgt:179cm
 hcl:#cfa07d eyr:2025 pid:166559648
 iyr:2011 ecl:brn hgt:59in";
 
-    #[test]
-    fn test_first_challenge() {
-        let day = Day04::load(INPUT);
-        assert_eq!(day.first_challenge(), "2");
-    }
-
-    #[test]
-    fn test_second_challenge_invalid() {
-        let input = "eyr:1972 cid:100
+const INPUT_2: &str = "eyr:1972 cid:100
 hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
 
 iyr:2019
@@ -260,13 +255,8 @@ ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
 hgt:59cm ecl:zzz
 eyr:2038 hcl:74454a iyr:2023
 pid:3556412378 byr:20076";
-        let day = Day04::load(input);
-        assert_eq!(day.second_challenge(), "0");
-    }
 
-    #[test]
-    fn test_second_challenge_valid() {
-        let input = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+const INPUT_3: &str = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
 hcl:#623a2f
 
 eyr:2029 ecl:blu cid:129 byr:1989
@@ -278,49 +268,58 @@ pid:545766238 ecl:hzl
 eyr:2022
 
 iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
-        let day = Day04::load(input);
-        assert_eq!(day.second_challenge(), "4");
-    }
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "2"),
+    Example::second(INPUT_2, "0"),
+    Example::second(INPUT_3, "4"),
+];
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn test_valid_year() {
-        let ref validator = YearValidator {
+        let validator = &YearValidator {
             key: "year",
             min: 1920,
             max: 2020,
         };
-        assert_eq!(validator.is_valid("2002"), true);
-        assert_eq!(validator.is_valid("2021"), false);
+        assert!(validator.is_valid("2002"));
+        assert!(!validator.is_valid("2021"));
     }
 
     #[test]
     fn test_valid_height() {
-        let ref validator = HeightValidator;
-        assert_eq!(validator.is_valid("60in"), true);
-        assert_eq!(validator.is_valid("190cm"), true);
-        assert_eq!(validator.is_valid("190in"), false);
-        assert_eq!(validator.is_valid("190"), false);
+        let validator = &HeightValidator;
+        assert!(validator.is_valid("60in"));
+        assert!(validator.is_valid("190cm"));
+        assert!(!validator.is_valid("190in"));
+        assert!(!validator.is_valid("190"));
     }
 
     #[test]
     fn test_valid_hair() {
-        let ref validator = HairValidator;
-        assert_eq!(validator.is_valid("#123abc"), true);
-        assert_eq!(validator.is_valid("#123abz"), false);
-        assert_eq!(validator.is_valid("123abc"), false);
+        let validator = &HairValidator;
+        assert!(validator.is_valid("#123abc"));
+        assert!(!validator.is_valid("#123abz"));
+        assert!(!validator.is_valid("123abc"));
     }
 
     #[test]
     fn test_valid_eye() {
-        let ref validator = EyeValidator;
-        assert_eq!(validator.is_valid("brn"), true);
-        assert_eq!(validator.is_valid("wat"), false);
+        let validator = &EyeValidator;
+        assert!(validator.is_valid("brn"));
+        assert!(!validator.is_valid("wat"));
     }
 
     #[test]
     fn test_valid_passport_id() {
-        let ref validator = PassportIdValidator;
-        assert_eq!(validator.is_valid("000000001"), true);
-        assert_eq!(validator.is_valid("0123456789"), false);
+        let validator = &PassportIdValidator;
+        assert!(validator.is_valid("000000001"));
+        assert!(!validator.is_valid("0123456789"));
     }
 }
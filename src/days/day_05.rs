@@ -1,4 +1,10 @@
-use super::Day;
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::{Error, Result};
+
+register_day!(5, "Binary Boarding", Day05, EXAMPLES);
 
 pub struct Day05 {
     input: Vec<Seat>,
@@ -10,57 +16,65 @@ struct Seat {
 }
 
 impl Day05 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    const SEAT_LENGTH: usize = 10;
+
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
     // Optimized for second challenge: I want to find my seat fast 'cause I'm tired!
     // O(N * log(N))
-    fn parse_input(s: &str) -> Vec<Seat> {
-        let mut input = s.lines().map(Self::parse_seat).collect::<Vec<_>>();
+    fn parse_input(s: &str) -> Result<Vec<Seat>> {
+        let mut input = parse::lines(s, 1, Self::parse_seat)?;
         input.sort_by_key(|seat| seat.value);
-        input
+        Ok(input)
     }
 
-    fn parse_seat(s: &str) -> Seat {
+    fn parse_seat(s: &str) -> LineResult<Seat> {
         let binary = s
-            .chars()
-            .map(|c| match c {
-                'F' => '0',
-                'B' => '1',
-                'L' => '0',
-                'R' => '1',
-                _ => panic!("invalid seat!"),
+            .char_indices()
+            .map(|(j, c)| match c {
+                'F' | 'L' => Ok('0'),
+                'B' | 'R' => Ok('1'),
+                _ => Err(LineError::new(j + 1, parse::invalid_char(c))),
             })
-            .collect::<String>();
+            .collect::<LineResult<String>>()?;
 
+        if binary.len() != Self::SEAT_LENGTH {
+            return Err(LineError::new(
+                binary.len().min(Self::SEAT_LENGTH) + 1,
+                format!("expected {} characters", Self::SEAT_LENGTH),
+            ));
+        }
+
+        // only 0s and 1s at this point
         let value = u32::from_str_radix(&binary, 2).unwrap();
         let row = u32::from_str_radix(&binary[0..7], 2).unwrap();
         let column = u32::from_str_radix(&binary[7..10], 2).unwrap();
 
-        Seat {
+        Ok(Seat {
             id: row * 8 + column,
             value,
-        }
+        })
     }
 }
 
 impl Day for Day05 {
     // O(N)
-    fn first_challenge(&self) -> String {
+    fn first_challenge(&self) -> Result<Answer> {
         self.input
             .iter()
             .map(|seat| seat.id)
             .max()
-            .unwrap()
-            .to_string()
+            .map(|id| id.into())
+            .ok_or(Error::NotFound)
     }
 
     // O(log(N))
-    fn second_challenge(&self) -> String {
-        let min = self.input[0].value;
+    fn second_challenge(&self) -> Result<Answer> {
+        let min = self.input.first().ok_or(Error::NotFound)?.value;
 
         let mut l = 0usize;
         let mut r = self.input.len();
@@ -75,30 +89,23 @@ impl Day for Day05 {
             }
         }
 
-        (self.input[r].id - 1).to_string()
+        self.input
+            .get(r)
+            .map(|seat| (seat.id - 1).into())
+            .ok_or(Error::NotFound)
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    #[test]
-    fn test_first_challenge() {
-        let input = "BFFFBBFRRR
+const INPUT_1: &str = "BFFFBBFRRR
 FFFBBBFRRR
 BBFFBBFRLL";
-        let day = Day05::load(input);
-        assert_eq!(day.first_challenge(), "820");
-    }
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "FFFFFFFFFB
+const INPUT_2: &str = "FFFFFFFFFB
 FFFFFFFFBB";
-        let day = Day05::load(input);
-        assert_eq!(day.second_challenge(), "2");
-    }
-}
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "820"),
+    Example::second(INPUT_2, "2"),
+];
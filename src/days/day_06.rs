@@ -1,4 +1,10 @@
-use super::Day;
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::Result;
+
+register_day!(6, "Custom Customs", Day06, EXAMPLES);
 
 pub struct Day06 {
     input: Vec<GroupAnswers>,
@@ -10,28 +16,26 @@ type GroupAnswers = Vec<PersonAnswers>;
 type PersonAnswers = u32;
 
 impl Day06 {
-    const BLANK_LINE: &'static str = "\n\n";
-
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
-    }
-
-    fn parse_input(s: &str) -> Vec<GroupAnswers> {
-        s.split(Self::BLANK_LINE)
-            .map(Self::parse_group_answers)
-            .collect()
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_group_answers(s: &str) -> GroupAnswers {
-        s.lines().map(Self::parse_person_answers).collect()
+    fn parse_input(s: &str) -> Result<Vec<GroupAnswers>> {
+        parse::all(
+            parse::sections(s)
+                .into_iter()
+                .map(|(first_line, group)| parse::lines(group, first_line, Self::parse_person_answers)),
+        )
     }
 
     // "daec" -> 1101
-    fn parse_person_answers(s: &str) -> PersonAnswers {
-        s.chars()
-            .fold(0, |acc, c| acc | (1 << (c as u32 - 'a' as u32)))
+    fn parse_person_answers(s: &str) -> LineResult<PersonAnswers> {
+        s.char_indices().try_fold(0, |acc, (j, c)| match c {
+            'a'..='z' => Ok(acc | (1 << (c as u32 - 'a' as u32))),
+            _ => Err(LineError::new(j + 1, parse::invalid_char(c))),
+        })
     }
 
     // 1101 -> 3
@@ -41,39 +45,37 @@ impl Day06 {
 }
 
 impl Day for Day06 {
-    fn first_challenge(&self) -> String {
-        self.input
+    fn first_challenge(&self) -> Result<Answer> {
+        let count = self
+            .input
             .iter()
             .map(|group| {
                 let union = group.iter().fold(0, |acc, answers| acc | answers);
                 Self::count_answers(union)
             })
-            .sum::<u32>()
-            .to_string()
+            .sum::<u32>();
+
+        Ok(count.into())
     }
 
-    fn second_challenge(&self) -> String {
-        self.input
+    fn second_challenge(&self) -> Result<Answer> {
+        let count = self
+            .input
             .iter()
             .map(|group| {
                 let all_yes = (1 << QUESTIONS) - 1;
                 let intersection = group.iter().fold(all_yes, |acc, answers| acc & answers);
                 Self::count_answers(intersection)
             })
-            .sum::<u32>()
-            .to_string()
+            .sum::<u32>();
+
+        Ok(count.into())
     }
 }
 
-/* tests */
+/* examples */
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_first_challenge() {
-        let input = "abc
+const INPUT: &str = "abc
 
 a
 b
@@ -88,28 +90,8 @@ a
 a
 
 b";
-        let day = Day06::load(input);
-        assert_eq!(day.first_challenge(), "11");
-    }
-
-    #[test]
-    fn test_second_challenge() {
-        let input = "abc
-
-a
-b
-c
-
-ab
-ac
-
-a
-a
-a
-a
 
-b";
-        let day = Day06::load(input);
-        assert_eq!(day.second_challenge(), "6");
-    }
-}
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "11"),
+    Example::second(INPUT, "6"),
+];
@@ -1,41 +1,107 @@
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use super::Day;
+use super::{
+    parse::{self, LineError, LineResult},
+    downcast, Answer, Day, Example, Query,
+};
+use crate::error::{Error, Result};
+
+register_day!(7, "Handy Haversacks", Day07, EXAMPLES, &[], QUERIES);
 
 pub struct Day07 {
     input: RuleSet,
 }
 
 type RuleSet = HashMap<Color, Vec<BagCount>>;
+type Rule = (Color, Vec<BagCount>);
 type BagCount = (Color, usize);
 type Color = String;
 
 impl Day07 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
+    pub fn load(input: &str) -> Result<Self> {
+        let rules = Self::parse_input(input)?;
+        let day = Self {
+            input: rules.iter().map(|(_, rule)| rule.clone()).collect(),
+        };
+
+        // a bag inside itself has no answer, and the searches below would never end
+        let mut done = HashSet::new();
+        for (_, (color, _)) in rules.iter() {
+            if let Some(color) = day.find_cycle(color, &mut HashSet::new(), &mut done) {
+                // the rule that counts, if there are many for the same color
+                let (line, _) = rules.iter().rev().find(|(_, (c, _))| c == color).unwrap();
+                let error = LineError::new(1, format!("{} bags end up inside themselves", color));
+                return Err(error.into_error(*line).into());
+            }
         }
+
+        Ok(day)
     }
 
-    fn parse_input(s: &str) -> RuleSet {
-        s.lines().map(Self::parse_rule).collect()
+    // each rule along with its line
+    fn parse_input(s: &str) -> Result<Vec<(usize, Rule)>> {
+        parse::all(s.lines().enumerate().map(|(i, line)| {
+            let rule = Self::parse_rule(line).map_err(|e| e.into_error(i + 1))?;
+            Ok((i + 1, rule))
+        }))
     }
 
-    fn parse_rule(s: &str) -> (Color, Vec<BagCount>) {
+    fn parse_rule(s: &str) -> LineResult<Rule> {
         lazy_static! {
             static ref SUBJECT_REGEX: Regex = Regex::new(r"^(?P<color>\w+ \w+) bags").unwrap();
             static ref OBJECT_REGEX: Regex =
                 Regex::new(r"(?P<count>\d+) (?P<color>\w+ \w+) bag[s]?[,\.]").unwrap();
         }
 
-        let color = SUBJECT_REGEX.captures(s).unwrap()["color"].to_owned();
+        let color = SUBJECT_REGEX
+            .captures(s)
+            .map(|c| c["color"].to_owned())
+            .ok_or_else(|| LineError::new(1, "missing bag color"))?;
+
         let contains = OBJECT_REGEX
             .captures_iter(s)
-            .map(|c| (c["color"].to_owned(), c["count"].parse::<usize>().unwrap()))
-            .collect();
+            .map(|c| {
+                let count = c.name("count").unwrap().as_str();
+                Ok((c["color"].to_owned(), parse::number(s, count)?))
+            })
+            .collect::<LineResult<_>>()?;
+
+        Ok((color, contains))
+    }
+
+    // a bag found inside itself while looking into `color`, with `path` the bags being looked
+    // into. Colors in `done` are known to lead nowhere, so each one is looked into once
+    fn find_cycle<'a>(
+        &'a self,
+        color: &'a str,
+        path: &mut HashSet<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<&'a str> {
+        if done.contains(color) {
+            return None;
+        }
+        if !path.insert(color) {
+            return Some(color);
+        }
+
+        for (inner, _) in self.input.get(color).into_iter().flatten() {
+            if let Some(cycle) = self.find_cycle(inner, path, done) {
+                return Some(cycle);
+            }
+        }
+
+        path.remove(color);
+        done.insert(color);
+        None
+    }
 
-        (color, contains)
+    // bags in the input without a rule are not worth asking about
+    fn rule(&self, color: &str) -> Result<&[BagCount]> {
+        self.input
+            .get(color.trim())
+            .map(Vec::as_slice)
+            .ok_or(Error::NotFound)
     }
 
     fn count_paths(&self, target: Color) -> usize {
@@ -43,7 +109,7 @@ impl Day07 {
         self.input
             .keys()
             .filter(|&color| color != &target)
-            .map(|color| self.count_paths_dfs(&color, &target, &mut memo))
+            .map(|color| self.count_paths_dfs(color, &target, &mut memo))
             .filter(|&paths| paths > 0)
             .count()
     }
@@ -62,8 +128,12 @@ impl Day07 {
             return memo[color];
         }
 
-        let paths = self.input[color]
-            .iter()
+        // bags without a rule are assumed to be empty
+        let paths = self
+            .input
+            .get(color)
+            .into_iter()
+            .flatten()
             .map(|(color, _)| self.count_paths_dfs(color, target, memo))
             .sum();
 
@@ -80,8 +150,11 @@ impl Day07 {
             return memo[color];
         }
 
-        let nested = self.input[color]
-            .iter()
+        let nested = self
+            .input
+            .get(color)
+            .into_iter()
+            .flatten()
             .map(|(color, bag_count)| self.count_nested_dfs(color, memo) * bag_count)
             .sum::<usize>();
 
@@ -91,41 +164,20 @@ impl Day07 {
 }
 
 impl Day for Day07 {
-    fn first_challenge(&self) -> String {
+    fn first_challenge(&self) -> Result<Answer> {
         let target = "shiny gold".to_string();
-        self.count_paths(target).to_string()
+        Ok(self.count_paths(target).into())
     }
 
-    fn second_challenge(&self) -> String {
+    fn second_challenge(&self) -> Result<Answer> {
         let source = "shiny gold".to_string();
-        self.count_nested(source).to_string()
+        Ok(self.count_nested(source).into())
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_first_challenge() {
-        let input = "light red bags contain 1 bright white bag, 2 muted yellow bags.
-dark orange bags contain 3 bright white bags, 4 muted yellow bags.
-bright white bags contain 1 shiny gold bag.
-muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
-shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
-dark olive bags contain 3 faded blue bags, 4 dotted black bags.
-vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
-faded blue bags contain no other bags.
-dotted black bags contain no other bags.";
-        let day = Day07::load(input);
-        assert_eq!(day.first_challenge(), "4");
-    }
+/* examples */
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+const INPUT_1: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
 dark orange bags contain 3 bright white bags, 4 muted yellow bags.
 bright white bags contain 1 shiny gold bag.
 muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
@@ -134,20 +186,66 @@ dark olive bags contain 3 faded blue bags, 4 dotted black bags.
 vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
 faded blue bags contain no other bags.
 dotted black bags contain no other bags.";
-        let day = Day07::load(input);
-        assert_eq!(day.second_challenge(), "32");
-    }
 
-    #[test]
-    fn test_second_challenge_2() {
-        let input = "shiny gold bags contain 2 dark red bags.
+const INPUT_2: &str = "shiny gold bags contain 2 dark red bags.
 dark red bags contain 2 dark orange bags.
 dark orange bags contain 2 dark yellow bags.
 dark yellow bags contain 2 dark green bags.
 dark green bags contain 2 dark blue bags.
 dark blue bags contain 2 dark violet bags.
 dark violet bags contain no other bags.";
-        let day = Day07::load(input);
-        assert_eq!(day.second_challenge(), "126");
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "4"),
+    Example::second(INPUT_1, "32"),
+    Example::second(INPUT_2, "126"),
+];
+
+/* queries */
+
+const QUERIES: &[Query] = &[
+    Query::new("contents", "<color>", |day, color| {
+        let contents = downcast::<Day07>(day).rule(color)?;
+        if contents.is_empty() {
+            return Ok("no other bags".into());
+        }
+
+        let bags = contents
+            .iter()
+            .map(|(color, count)| format!("{} {}", count, color))
+            .collect::<Vec<_>>();
+        Ok(bags.join(", ").into())
+    }),
+    Query::new("holders", "<color>", |day, color| {
+        let day = downcast::<Day07>(day);
+        day.rule(color)?;
+        Ok(day.count_paths(color.trim().to_string()).into())
+    }),
+    Query::new("nested", "<color>", |day, color| {
+        let day = downcast::<Day07>(day);
+        day.rule(color)?;
+        Ok(day.count_nested(color.trim().to_string()).into())
+    }),
+];
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle() {
+        let input = "shiny gold bags contain 1 a b bag.
+a b bags contain 1 c d bag.
+c d bags contain 2 a b bags.";
+        let error = Day07::load(input).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 2:1: a b bags end up inside themselves"
+        );
+
+        let error = Day07::load("a b bags contain 1 a b bag.").err().unwrap();
+        assert!(error.to_string().starts_with("parse error at 1:1"));
     }
 }
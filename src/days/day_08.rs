@@ -1,6 +1,10 @@
-use itertools::Itertools;
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::{Error, Result};
 
-use super::Day;
+register_day!(8, "Handheld Halting", Day08, EXAMPLES);
 
 pub struct Day08 {
     input: Program,
@@ -17,27 +21,27 @@ enum Op {
 type State = (usize, i32);
 
 impl Day08 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Program {
-        s.lines().map(Self::parse_instruction).collect()
+    fn parse_input(s: &str) -> Result<Program> {
+        parse::lines(s, 1, Self::parse_instruction)
     }
 
-    fn parse_instruction(s: &str) -> Instruction {
-        let (op, arg) = s.splitn(2, ' ').collect_tuple().unwrap();
-        (Self::parse_op(op), arg.parse::<i32>().unwrap())
+    fn parse_instruction(s: &str) -> LineResult<Instruction> {
+        let (op, arg) = parse::split_pair(s, s, " ")?;
+        Ok((Self::parse_op(s, op)?, parse::number(s, arg)?))
     }
 
-    fn parse_op(s: &str) -> Op {
+    fn parse_op(line: &str, s: &str) -> LineResult<Op> {
         match s {
-            "nop" => Op::Nop,
-            "acc" => Op::Acc,
-            "jmp" => Op::Jmp,
-            _ => panic!("Invalid operation!"),
+            "nop" => Ok(Op::Nop),
+            "acc" => Ok(Op::Acc),
+            "jmp" => Ok(Op::Jmp),
+            _ => Err(LineError::at(line, s, format!("invalid operation '{}'", s))),
         }
     }
 
@@ -87,7 +91,7 @@ impl Day08 {
 
 impl Day for Day08 {
     // O(N)
-    fn first_challenge(&self) -> String {
+    fn first_challenge(&self) -> Result<Answer> {
         let cycle_start = self.find_cycle_start();
 
         // continue until we reach cycle start again
@@ -97,11 +101,11 @@ impl Day for Day08 {
             state = self.next(state);
         }
 
-        state.1.to_string()
+        Ok(state.1.into())
     }
 
     // O(N^2)
-    fn second_challenge(&self) -> String {
+    fn second_challenge(&self) -> Result<Answer> {
         let to_switch = self
             .input
             .iter()
@@ -121,25 +125,19 @@ impl Day for Day08 {
 
             let cycle_start = mut_self.find_cycle_start();
             if mut_self.is_final(&cycle_start) {
-                return cycle_start.1.to_string();
+                return Ok(cycle_start.1.into());
             }
 
             mut_self.input[i] = Self::switch(mut_self.input[i]);
         }
 
-        "NOT_FOUND".to_string()
+        Err(Error::NotFound)
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    #[test]
-    fn test_first_challenge() {
-        let input = "nop +0
+const INPUT: &str = "nop +0
 acc +1
 jmp +4
 acc +3
@@ -148,22 +146,8 @@ acc -99
 acc +1
 jmp -4
 acc +6";
-        let day = Day08::load(input);
-        assert_eq!(day.first_challenge(), "5");
-    }
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "nop +0
-acc +1
-jmp +4
-acc +3
-jmp -3
-acc -99
-acc +1
-jmp -4
-acc +6";
-        let day = Day08::load(input);
-        assert_eq!(day.second_challenge(), "8");
-    }
-}
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "5"),
+    Example::second(INPUT, "8"),
+];
@@ -1,27 +1,32 @@
 use std::collections::HashSet;
 
-use super::Day;
+use super::{parse, Answer, Day};
+use crate::error::{Error, Result};
+
+register_day!(9, "Encoding Error", Day09);
 
 pub struct Day09 {
     input: Vec<i64>,
 }
 
 impl Day09 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    const WINDOW_SIZE: usize = 25;
+
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Vec<i64> {
-        s.lines().map(|l| l.parse::<i64>().unwrap()).collect()
+    fn parse_input(s: &str) -> Result<Vec<i64>> {
+        parse::lines(s, 1, |line| parse::number(line, line))
     }
 
     fn find_error(&self, window_size: usize) -> Option<i64> {
         let window = &mut HashSet::new();
 
-        for i in 0..window_size {
-            window.insert(self.input[i]);
+        for &x in self.input.iter().take(window_size) {
+            window.insert(x);
         }
 
         for i in window_size..self.input.len() {
@@ -40,7 +45,7 @@ impl Day09 {
     }
 
     fn find_range_with_sum(&self, target: i64) -> Option<&[i64]> {
-        let (mut i, mut j, mut sum) = (0, 0, self.input[0]);
+        let (mut i, mut j, mut sum) = (0, 0, *self.input.first()?);
 
         while i < self.input.len() {
             if sum == target && j > i {
@@ -65,14 +70,17 @@ impl Day09 {
 }
 
 impl Day for Day09 {
-    fn first_challenge(&self) -> String {
-        self.find_error(25).unwrap().to_string()
+    fn first_challenge(&self) -> Result<Answer> {
+        self.find_error(Self::WINDOW_SIZE)
+            .map(|error| error.into())
+            .ok_or(Error::NotFound)
     }
 
-    fn second_challenge(&self) -> String {
-        self.find_range_with_sum(1492208709)
-            .map(|slice| self.encryption_weakness(slice).to_string())
-            .unwrap_or_else(|| "NOT_FOUND".to_string())
+    fn second_challenge(&self) -> Result<Answer> {
+        self.find_error(Self::WINDOW_SIZE)
+            .and_then(|error| self.find_range_with_sum(error))
+            .map(|slice| self.encryption_weakness(slice).into())
+            .ok_or(Error::NotFound)
     }
 }
 
@@ -105,7 +113,7 @@ mod tests {
 309
 576";
         let day = Day09 {
-            input: Day09::parse_input(input),
+            input: Day09::parse_input(input).unwrap(),
         };
         assert_eq!(day.find_error(5), Some(127));
     }
@@ -133,7 +141,7 @@ mod tests {
 309
 576";
         let day = Day09 {
-            input: Day09::parse_input(input),
+            input: Day09::parse_input(input).unwrap(),
         };
         assert_eq!(
             day.find_range_with_sum(127)
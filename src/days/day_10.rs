@@ -1,32 +1,33 @@
-use super::Day;
+use super::{parse, Answer, Day, Example};
+use crate::error::Result;
+
+register_day!(10, "Adapter Array", Day10, EXAMPLES);
 
 pub struct Day10 {
     input: Vec<i64>,
 }
 
 impl Day10 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Vec<i64> {
-        let mut vec = s
-            .lines()
-            .map(|l| l.parse::<i64>().unwrap())
-            .collect::<Vec<i64>>();
+    fn parse_input(s: &str) -> Result<Vec<i64>> {
+        let mut vec = parse::lines(s, 1, |line| parse::number::<i64>(line, line))?;
 
+        // there is always the outlet, so max exists
         vec.push(0);
         vec.push(vec.iter().max().unwrap() + 3);
 
         vec.sort_unstable();
-        vec
+        Ok(vec)
     }
 }
 
 impl Day for Day10 {
-    fn first_challenge(&self) -> String {
+    fn first_challenge(&self) -> Result<Answer> {
         let (ones, threes) =
             self.input
                 .windows(2)
@@ -37,10 +38,10 @@ impl Day for Day10 {
                     _ => (ones, threes),
                 });
 
-        (ones * threes).to_string()
+        Ok((ones * threes).into())
     }
 
-    fn second_challenge(&self) -> String {
+    fn second_challenge(&self) -> Result<Answer> {
         let mut combinations = vec![0u64; self.input.len()];
 
         combinations[0] = 1;
@@ -52,19 +53,13 @@ impl Day for Day10 {
             }
         }
 
-        combinations.last().unwrap().to_string()
+        Ok((*combinations.last().unwrap()).into())
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    #[test]
-    fn test_first_challenge() {
-        let input = "28
+const INPUT_1: &str = "28
 33
 18
 42
@@ -95,13 +90,8 @@ mod tests {
 34
 10
 3";
-        let day = Day10::load(input);
-        assert_eq!(day.first_challenge(), "220");
-    }
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "16
+const INPUT_2: &str = "16
 10
 15
 5
@@ -112,44 +102,9 @@ mod tests {
 6
 12
 4";
-        let day = Day10::load(input);
-        assert_eq!(day.second_challenge(), "8");
-    }
 
-    #[test]
-    fn test_second_challenge_large() {
-        let input = "28
-33
-18
-42
-31
-14
-46
-20
-48
-47
-24
-23
-49
-45
-19
-38
-39
-11
-1
-32
-25
-35
-8
-17
-7
-9
-4
-2
-34
-10
-3";
-        let day = Day10::load(input);
-        assert_eq!(day.second_challenge(), "19208");
-    }
-}
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "220"),
+    Example::second(INPUT_2, "8"),
+    Example::second(INPUT_1, "19208"),
+];
@@ -1,6 +1,13 @@
 use std::cmp::min;
 
-use super::Day;
+use super::{parse, Answer, Day, Example, Part};
+use crate::{
+    cancel,
+    error::Result,
+    frame::{Frame, Image},
+};
+
+register_day!(11, "Seating System", Day11, EXAMPLES);
 
 pub struct Day11 {
     input: State,
@@ -22,24 +29,48 @@ type Line = Vec<Coord>;
 type LineSlice<'a> = &'a [Coord];
 
 impl Day11 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> State {
-        s.lines()
-            .map(|l| l.chars().map(Self::parse_slot).collect::<Vec<_>>())
-            .collect::<Vec<_>>()
+    fn parse_input(s: &str) -> Result<State> {
+        parse::grid(s, 1, Self::parse_slot)
     }
 
-    fn parse_slot(c: char) -> Slot {
+    fn parse_slot(c: char) -> Option<Slot> {
         match c {
-            'L' => Slot::Empty,
-            '#' => Slot::Occupied,
-            '.' => Slot::Floor,
-            _ => panic!("Invalid slot!"),
+            'L' => Some(Slot::Empty),
+            '#' => Some(Slot::Occupied),
+            '.' => Some(Slot::Floor),
+            _ => None,
+        }
+    }
+
+    // runs the automaton until nothing changes anymore, `visit` sees each state on the way
+    fn settle<F>(&self, part: Part, mut visit: F) -> Result<usize>
+    where
+        F: FnMut(StateSlice),
+    {
+        let state = &mut self.input.to_vec();
+        let (threshold, lines) = match part {
+            Part::First => (4, None),
+            Part::Second => (5, Some(Self::generate_lines(state))),
+        };
+
+        loop {
+            cancel::check()?;
+            visit(state);
+
+            let occupied = &match &lines {
+                None => Self::count_occupied_adjacent(state),
+                Some(lines) => Self::count_occupied_view(state, lines),
+            };
+            if Self::is_stable(state, occupied, threshold) {
+                return Ok(Self::count_occupied(state));
+            }
+            Self::update(state, occupied, threshold);
         }
     }
 
@@ -180,48 +211,39 @@ impl Day11 {
     fn count_occupied(state: StateSlice) -> usize {
         state
             .iter()
-            .map(|row| row.iter().filter(|&slot| slot == &Slot::Occupied))
-            .flatten()
+            .flat_map(|row| row.iter().filter(|&slot| slot == &Slot::Occupied))
             .count()
     }
 }
 
 impl Day for Day11 {
-    fn first_challenge(&self) -> String {
-        let threshold = 4;
-        let state = &mut self.input.to_vec();
-        loop {
-            let occupied = &Self::count_occupied_adjacent(state);
-            if Self::is_stable(state, occupied, threshold) {
-                return Self::count_occupied(&state).to_string();
-            }
-            Self::update(state, occupied, threshold);
-        }
+    fn first_challenge(&self) -> Result<Answer> {
+        Ok(self.settle(Part::First, |_| ())?.into())
     }
 
-    fn second_challenge(&self) -> String {
-        let threshold = 5;
-        let state = &mut self.input.to_vec();
-        let lines = &Self::generate_lines(state);
-        loop {
-            let occupied = &Self::count_occupied_view(state, lines);
-            if Self::is_stable(state, occupied, threshold) {
-                return Self::count_occupied(&state).to_string();
-            }
-            Self::update(state, occupied, threshold);
-        }
+    fn second_challenge(&self) -> Result<Answer> {
+        Ok(self.settle(Part::Second, |_| ())?.into())
     }
-}
 
-/* tests */
+    // one image per round: people come and go until everyone is happy with their seat
+    fn visualize(&self, part: Part) -> Result<Vec<Frame>> {
+        let mut frames = Vec::new();
+        self.settle(part, |state| {
+            let image = Image::from_grid(state, 4, |slot| match slot {
+                Slot::Floor => [40, 40, 40],
+                Slot::Empty => [60, 160, 60],
+                Slot::Occupied => [200, 60, 60],
+            });
+            frames.push(Frame::Image(image));
+        })?;
+
+        Ok(frames)
+    }
+}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    #[test]
-    fn test_first_challenge() {
-        let input = "L.LL.LL.LL
+const INPUT: &str = "L.LL.LL.LL
 LLLLLLL.LL
 L.L.L..L..
 LLLL.LL.LL
@@ -231,23 +253,26 @@ L.LLLLL.LL
 LLLLLLLLLL
 L.LLLLLL.L
 L.LLLLL.LL";
-        let day = Day11::load(input);
-        assert_eq!(day.first_challenge(), "37");
-    }
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "37"),
+    Example::second(INPUT, "26"),
+];
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn test_second_challenge() {
-        let input = "L.LL.LL.LL
-LLLLLLL.LL
-L.L.L..L..
-LLLL.LL.LL
-L.LL.LL.LL
-L.LLLLL.LL
-..L.L.....
-LLLLLLLLLL
-L.LLLLLL.L
-L.LLLLL.LL";
-        let day = Day11::load(input);
-        assert_eq!(day.second_challenge(), "26");
+    fn test_invalid_slot() {
+        let input = "L.LL
+L.?L";
+        let error = Day11::load(input).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 2:3: invalid character '?'"
+        );
     }
 }
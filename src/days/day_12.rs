@@ -1,47 +1,107 @@
-use std::{error::Error, str::FromStr};
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example, Part,
+};
+use crate::{
+    error::Result,
+    frame::{Frame, Image},
+};
 
-use super::Day;
+register_day!(12, "Rain Risk", Day12, EXAMPLES);
 
 pub struct Day12 {
     input: Vec<Action>,
 }
 
 impl Day12 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
+    }
+
+    fn parse_input(s: &str) -> Result<Vec<Action>> {
+        parse::lines(s, 1, Action::parse)
+    }
+
+    // where the ship is after each action, starting from the origin
+    fn path(&self, part: Part) -> Vec<Position> {
+        let start = Position::new();
+        let waypoint = Position {
+            x: 10,
+            y: 1,
+            dir: Direction::East,
+        };
+
+        let mut path = vec![start];
+        match part {
+            Part::First => path.extend(self.input.iter().scan(start, |ship, action| {
+                *ship = NavigatorOld::next(*ship, action);
+                Some(*ship)
+            })),
+            Part::Second => path.extend(self.input.iter().scan(
+                (start, waypoint),
+                |state, action| {
+                    *state = NavigatorNew::next(*state, action);
+                    Some(state.0)
+                },
+            )),
         }
+
+        path
     }
 
-    fn parse_input(s: &str) -> Vec<Action> {
-        s.lines()
-            .map(Action::from_str)
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap()
+    fn distance(&self, part: Part) -> i32 {
+        let path = self.path(part);
+        path[path.len() - 1].distance(path[0])
     }
 }
 
 impl Day for Day12 {
-    fn first_challenge(&self) -> String {
-        let start = Position::new();
-        let end = self.input.iter().fold(start, NavigatorOld::next);
+    fn first_challenge(&self) -> Result<Answer> {
+        Ok(self.distance(Part::First).into())
+    }
 
-        end.distance(start).to_string()
+    fn second_challenge(&self) -> Result<Answer> {
+        Ok(self.distance(Part::Second).into())
     }
 
-    fn second_challenge(&self) -> String {
-        let start = Position::new();
-        let waypoint = Position {
-            x: 10,
-            y: 1,
-            dir: Direction::East,
+    // the route of the ship drawn a bit at a time, north is up
+    fn visualize(&self, part: Part) -> Result<Vec<Frame>> {
+        const SIZE: usize = 300;
+        const FRAMES: usize = 20;
+
+        let path = self.path(part);
+        // the path starts from the origin, it is never empty
+        let bounds = |coord: fn(&Position) -> i32| {
+            let values = path.iter().map(coord);
+            (values.clone().min().unwrap(), values.max().unwrap())
         };
-        let (end, _) = self
-            .input
-            .iter()
-            .fold((start, waypoint), NavigatorNew::next);
+        let (min_x, max_x) = bounds(|p| p.x);
+        let (min_y, max_y) = bounds(|p| p.y);
+
+        // keep the proportions, the longest side fills the image
+        let span = (max_x - min_x).max(max_y - min_y).max(1) as f64;
+        let scale = |delta: i32| (delta as f64 / span * (SIZE - 1) as f64).round() as usize;
+        let pixel = |p: &Position| (scale(p.x - min_x), scale(max_y - p.y));
+
+        let (width, height) = (scale(max_x - min_x) + 1, scale(max_y - min_y) + 1);
+        let mut image = Image::new(width, height, [10, 20, 60]);
+        let every = ((path.len() - 1) / FRAMES).max(1);
+        let mut frames = Vec::new();
 
-        end.distance(start).to_string()
+        for (i, pair) in path.windows(2).enumerate() {
+            image.line(pixel(&pair[0]), pixel(&pair[1]), [200, 200, 200]);
+
+            if (i + 1) % every == 0 || i + 2 == path.len() {
+                let mut frame = image.clone();
+                let (x, y) = pixel(&pair[1]);
+                frame.fill(x.saturating_sub(2), y.saturating_sub(2), 5, [230, 60, 60]);
+                frames.push(Frame::Image(frame));
+            }
+        }
+
+        Ok(frames)
     }
 }
 
@@ -101,6 +161,7 @@ impl Position {
 
     fn rotate_origin(&self, degree: i32) -> Self {
         match degree {
+            0 => *self,
             90 => Self {
                 x: self.y,
                 y: -self.x,
@@ -116,7 +177,7 @@ impl Position {
                 y: self.x,
                 dir: self.dir,
             },
-            _ => panic!("Unsupported rotation"),
+            _ => unreachable!("rotations are validated while parsing"),
         }
     }
 
@@ -132,21 +193,28 @@ enum Action {
     Forward(i32),
 }
 
-impl FromStr for Action {
-    type Err = Box<dyn Error>;
+impl Action {
+    fn parse(s: &str) -> LineResult<Self> {
+        let letter = s
+            .chars()
+            .next()
+            .ok_or_else(|| LineError::new(1, "missing action"))?;
+        let digits = &s[letter.len_utf8()..];
+        let number = parse::number::<i32>(s, digits)?;
+
+        if "LR".contains(letter) && number.rem_euclid(90) != 0 {
+            return Err(LineError::at(s, digits, "rotations must be multiple of 90"));
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let letter = s.chars().next().unwrap();
-        let number = s[1..].parse::<i32>()?;
         let action = match letter {
             'E' => Self::Move(number, 0),
             'W' => Self::Move(-number, 0),
             'N' => Self::Move(0, number),
             'S' => Self::Move(0, -number),
-            'L' => Self::Rotate(360 - number),
-            'R' => Self::Rotate(number),
+            'L' => Self::Rotate((360 - number).rem_euclid(360)),
+            'R' => Self::Rotate(number.rem_euclid(360)),
             'F' => Self::Forward(number),
-            _ => panic!("Invalid action"),
+            _ => return Err(LineError::new(1, format!("invalid action '{}'", letter))),
         };
         Ok(action)
     }
@@ -185,31 +253,15 @@ impl NavigatorNew {
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    #[test]
-    fn test_first_challenge() {
-        let input = "F10
+const INPUT: &str = "F10
 N3
 F7
 R90
 F11";
-        let day = Day12::load(input);
-        assert_eq!(day.first_challenge(), "25");
-    }
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "F10
-N3
-F7
-R90
-F11";
-        let day = Day12::load(input);
-        assert_eq!(day.second_challenge(), "286");
-    }
-}
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "25"),
+    Example::second(INPUT, "286"),
+];
@@ -1,6 +1,13 @@
 use num::Integer;
 
-use super::Day;
+use super::{
+    downcast,
+    parse::{self, LineError},
+    Answer, Day, Example, Implementation,
+};
+use crate::error::{Error, Result};
+
+register_day!(13, "Shuttle Search", Day13, EXAMPLES, IMPLEMENTATIONS);
 
 pub struct Day13 {
     target: usize,
@@ -8,30 +15,65 @@ pub struct Day13 {
 }
 
 impl Day13 {
-    pub fn load(input: &str) -> Self {
-        let target = input.lines().next().unwrap().parse::<usize>().unwrap();
-        let buses = input
-            .lines()
-            .skip(1)
-            .next()
-            .unwrap()
+    pub fn load(input: &str) -> Result<Self> {
+        let mut lines = input.lines().chain(std::iter::repeat(""));
+
+        let target = lines.next().unwrap();
+        let target = parse::number(target, target).map_err(|e| e.into_error(1))?;
+
+        let buses = lines.next().unwrap();
+        let buses = buses
             .split(',')
-            .map(|id| id.parse::<usize>().ok())
-            .collect::<Vec<_>>();
-        Self { target, buses }
+            .map(|id| match id {
+                "x" => Ok(None),
+                _ => match parse::number::<usize>(buses, id)? {
+                    0 => Err(LineError::at(buses, id, "invalid bus id 0")),
+                    id => Ok(Some(id)),
+                },
+            })
+            .collect::<parse::LineResult<Vec<_>>>()
+            .map_err(|e| e.into_error(2))?;
+
+        Ok(Self { target, buses })
+    }
+
+    // the one my college math teacher would have approved: the chinese remainder theorem,
+    // with no brute force at all thanks to the extended euclidean algorithm
+    fn chinese_remainder(&self) -> Result<Answer> {
+        let (result, _) = self
+            .buses
+            .iter()
+            .enumerate()
+            .filter_map(|(delta, maybe_id)| maybe_id.map(|id| (id as i128, delta as i128)))
+            .try_fold((0i128, 1i128), |(result, period), (id, delta)| {
+                // (res + delta) % id = 0 -> res % id = remainder
+                let remainder = (-delta).rem_euclid(id);
+
+                // period * p + id * q = gcd -> period * p = gcd (mod id)
+                let gcd = period.extended_gcd(&id);
+                if (remainder - result) % gcd.gcd != 0 {
+                    return Err(Error::NotFound);
+                }
+
+                let modulo = id / gcd.gcd;
+                let steps = ((remainder - result) / gcd.gcd * gcd.x).rem_euclid(modulo);
+                let new_period = period * modulo;
+                Ok(((result + period * steps).rem_euclid(new_period), new_period))
+            })?;
+
+        Ok(result.into())
     }
 }
 
 impl Day for Day13 {
-    fn first_challenge(&self) -> String {
+    fn first_challenge(&self) -> Result<Answer> {
         let mut deltas = self
             .buses
             .iter()
             .filter_map(|&maybe_id| maybe_id)
             .map(|id| {
-                let next_trip = self.target / id + (self.target % id != 0) as usize;
-                let next_trip_time = next_trip * id;
-                (id, next_trip_time - self.target)
+                // time to wait for the next trip
+                (id, (id - self.target % id) % id)
             })
             .collect::<Vec<_>>();
 
@@ -39,9 +81,8 @@ impl Day for Day13 {
 
         deltas
             .first()
-            .map(|(id, delta)| id * delta)
-            .unwrap()
-            .to_string()
+            .map(|(id, delta)| (id * delta).into())
+            .ok_or(Error::NotFound)
     }
 
     // For this one I have to thank an old friend: I owe you another one... Wolfram Alpha!
@@ -50,7 +91,7 @@ impl Day for Day13 {
     // -> find res s.t. {res % id_0 = 0; (res + i) % id_i = 0}
     // -> note that res has period lcm(id_0, id_i)
     // -> rinse and repeat with next i
-    fn second_challenge(&self) -> String {
+    fn second_challenge(&self) -> Result<Answer> {
         let (result, _) = self
             .buses
             .iter()
@@ -63,55 +104,40 @@ impl Day for Day13 {
                     (id, remainder)
                 })
             })
-            .fold((0, 1), |(result, period), (id, remainder)| {
+            .try_fold((0, 1), |(result, period), (id, remainder)| {
                 // solve equation by brute force... I can feel the disapproval of my college math teacher :(
                 let new_result = (0..=id)
                     .map(|i| result + period * i)
                     .find(|res| res % id == remainder)
-                    .unwrap();
+                    .ok_or(Error::NotFound)?;
                 let new_period = period.lcm(&id);
-                (new_result, new_period)
-            });
+                Ok::<_, Error>((new_result, new_period))
+            })?;
 
-        result.to_string()
+        Ok(result.into())
     }
 }
 
-/* tests */
+/* examples */
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_first_challenge() {
-        let input = "939
+const INPUT_1: &str = "939
 7,13,x,x,59,x,31,19";
-        let day = Day13::load(input);
-        assert_eq!(day.first_challenge(), "295");
-    }
-
-    #[test]
-    fn test_second_challenge_1() {
-        let input = "939
-7,13,x,x,59,x,31,19";
-        let day = Day13::load(input);
-        assert_eq!(day.second_challenge(), "1068781");
-    }
 
-    #[test]
-    fn test_second_challenge_2() {
-        let input = "939
+const INPUT_2: &str = "939
 17,x,13,19";
-        let day = Day13::load(input);
-        assert_eq!(day.second_challenge(), "3417");
-    }
 
-    #[test]
-    fn test_second_challenge_3() {
-        let input = "939
+const INPUT_3: &str = "939
 67,7,59,61";
-        let day = Day13::load(input);
-        assert_eq!(day.second_challenge(), "754018");
-    }
-}
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "295"),
+    Example::second(INPUT_1, "1068781"),
+    Example::second(INPUT_2, "3417"),
+    Example::second(INPUT_3, "754018"),
+];
+
+/* implementations */
+
+const IMPLEMENTATIONS: &[Implementation] = &[Implementation::second("chinese remainder", |day| {
+    downcast::<Day13>(day).chinese_remainder()
+})];
@@ -1,56 +1,72 @@
-use itertools::Itertools;
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::Result;
 
-use super::Day;
+register_day!(14, "Docking Data", Day14, EXAMPLES);
 
 pub struct Day14 {
     input: Vec<Op>,
 }
 
 impl Day14 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    const MASK_LENGTH: usize = 36;
+
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Vec<Op> {
-        s.lines().map(Self::parse_op).collect::<Vec<_>>()
+    fn parse_input(s: &str) -> Result<Vec<Op>> {
+        parse::lines(s, 1, Self::parse_op)
     }
 
-    fn parse_op(s: &str) -> Op {
-        match &s[..3] {
-            "mas" => {
-                let mask = s.split("= ").skip(1).next().unwrap();
-                Op::Mask(mask.to_string())
-            }
+    fn parse_op(s: &str) -> LineResult<Op> {
+        let (target, value) = parse::split_pair(s, s, " = ")?;
+
+        if target == "mask" {
+            return Self::parse_mask(s, value).map(Op::Mask);
+        }
 
-            "mem" => {
-                let (i, j) = (s.find('[').unwrap() + 1, s.find(']').unwrap());
-                let key = s[i..j].parse::<usize>().unwrap();
+        let key = target
+            .strip_prefix("mem[")
+            .and_then(|key| key.strip_suffix(']'))
+            .ok_or_else(|| LineError::at(s, target, format!("unknown operation '{}'", target)))?;
 
-                let (_, value_str) = s.splitn(2, "= ").collect_tuple().unwrap();
-                let value = value_str.parse::<usize>().unwrap();
+        Ok(Op::Mem(parse::number(s, key)?, parse::number(s, value)?))
+    }
 
-                Op::Mem(key, value)
-            }
+    fn parse_mask(line: &str, s: &str) -> LineResult<String> {
+        if let Some((j, c)) = s.char_indices().find(|(_, c)| !"01X".contains(*c)) {
+            return Err(LineError::at(line, &s[j..], parse::invalid_char(c)));
+        }
 
-            _ => panic!("Unknown operation"),
+        if s.len() != Self::MASK_LENGTH {
+            return Err(LineError::at(
+                line,
+                s,
+                format!("expected {} bits, found {}", Self::MASK_LENGTH, s.len()),
+            ));
         }
+
+        Ok(s.to_string())
     }
 }
 
 impl Day for Day14 {
-    fn first_challenge(&self) -> String {
+    fn first_challenge(&self) -> Result<Answer> {
         let mut state = v1::State::default();
         self.input.iter().for_each(|op| state.apply(op));
-        state.total_sum().to_string()
+        Ok(state.total_sum().into())
     }
 
     // my original super clever solution was wrong... but was wrong 30x faster :)
-    fn second_challenge(&self) -> String {
+    fn second_challenge(&self) -> Result<Answer> {
         let mut state = v2::State::default();
         self.input.iter().for_each(|op| state.apply(op));
-        state.total_sum().to_string()
+        Ok(state.total_sum().into())
     }
 }
 
@@ -103,7 +119,7 @@ mod v1 {
         }
 
         fn apply(&self, x: usize) -> usize {
-            x & self.and | &self.or
+            x & self.and | self.or
         }
     }
 }
@@ -123,14 +139,13 @@ mod v2 {
         pub(super) fn apply(&mut self, op: &Op) {
             match op {
                 Op::Mask(bits) => {
-                    self.mask = Mask::from_str(&bits);
+                    self.mask = Mask::from_str(bits);
                 }
 
                 Op::Mem(key, value) => {
-                    self.mask
-                        .apply(*key)
-                        .iter()
-                        .for_each(|&key| drop(self.registers.insert(key, *value)));
+                    self.mask.apply(*key).iter().for_each(|&key| {
+                        self.registers.insert(key, *value);
+                    });
                 }
             }
         }
@@ -195,29 +210,19 @@ mod v2 {
     }
 }
 
-/* tests */
+/* examples */
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_first_challenge() {
-        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+const INPUT_1: &str = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
 mem[8] = 11
 mem[7] = 101
 mem[8] = 0";
-        let day = Day14::load(input);
-        assert_eq!(day.first_challenge(), "165");
-    }
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "mask = 000000000000000000000000000000X1001X
+const INPUT_2: &str = "mask = 000000000000000000000000000000X1001X
 mem[42] = 100
 mask = 00000000000000000000000000000000X0XX
 mem[26] = 1";
-        let day = Day14::load(input);
-        assert_eq!(day.second_challenge(), "208");
-    }
-}
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "165"),
+    Example::second(INPUT_2, "208"),
+];
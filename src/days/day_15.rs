@@ -1,35 +1,84 @@
 use std::collections::HashMap;
 
-use super::Day;
+use super::{downcast, parse, Answer, Day, Example, Implementation, Query};
+use crate::{cancel, error::Result};
+
+register_day!(15, "Rambunctious Recitation", Day15, EXAMPLES, IMPLEMENTATIONS, QUERIES);
 
 pub struct Day15 {
     input: Vec<usize>,
 }
 
 impl Day15 {
-    pub fn load(input: &str) -> Self {
-        let input = input
+    pub fn load(input: &str) -> Result<Self> {
+        let line = input.trim_end();
+        let input = line
             .split(',')
-            .map(|n| n.parse::<usize>().unwrap())
-            .collect::<Vec<_>>();
+            .map(|n| parse::number::<usize>(line, n))
+            .collect::<parse::LineResult<Vec<_>>>()
+            .map_err(|e| e.into_error(1))?;
+
+        Ok(Self { input })
+    }
+
+    fn spoken(&self, turn: usize) -> Result<usize> {
+        let n = match turn.checked_sub(self.input.len() + 1) {
+            Some(n) => n,
+            None => return Ok(self.input[turn - 1]),
+        };
+
+        let mut game = Game::new(&self.input);
+        for i in 0..n {
+            // millions of turns take a while
+            if i % 1_000_000 == 0 {
+                cancel::check()?;
+            }
+            game.next();
+        }
+
+        Ok(game.next().unwrap())
+    }
+
+    // no number spoken after the starting ones can be larger than the turn,
+    // so a plain vector can replace the hash map... and it is way faster
+    fn spoken_vec(&self, turn: usize) -> Result<usize> {
+        let start = self.input.len();
+        if turn <= start {
+            return Ok(self.input[turn - 1]);
+        }
+
+        // turn in which each number was last spoken, 0 for never
+        let size = self.input.iter().max().map_or(turn, |&max| turn.max(max + 1));
+        let mut last_seen = vec![0u32; size];
+        for (i, &n) in self.input[..start - 1].iter().enumerate() {
+            last_seen[n] = (i + 1) as u32;
+        }
+
+        let mut last = self.input[start - 1];
+        for t in start..turn {
+            if t % 1_000_000 == 0 {
+                cancel::check()?;
+            }
+
+            let next = match last_seen[last] as usize {
+                0 => 0,
+                seen => t - seen,
+            };
+            last_seen[last] = t as u32;
+            last = next;
+        }
 
-        Self { input }
+        Ok(last)
     }
 }
 
 impl Day for Day15 {
-    fn first_challenge(&self) -> String {
-        Game::new(&self.input)
-            .nth(2020 - self.input.len() - 1)
-            .unwrap()
-            .to_string()
+    fn first_challenge(&self) -> Result<Answer> {
+        Ok(self.spoken(2020)?.into())
     }
 
-    fn second_challenge(&self) -> String {
-        Game::new(&self.input)
-            .nth(30000000 - self.input.len() - 1)
-            .unwrap()
-            .to_string()
+    fn second_challenge(&self) -> Result<Answer> {
+        Ok(self.spoken(30000000)?.into())
     }
 }
 
@@ -42,9 +91,11 @@ struct Game {
 
 impl Game {
     fn new(numbers: &[usize]) -> Self {
-        let mut game = Game::default();
+        let mut game = Game {
+            last: numbers[0],
+            ..Default::default()
+        };
 
-        game.last = numbers[0];
         for &n in numbers.iter().skip(1) {
             game.last_seen.insert(game.last, game.index);
             game.index += 1;
@@ -73,45 +124,44 @@ impl Iterator for Game {
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_first_challenge() {
-        let input = "0,3,6";
-        let day = Day15::load(input);
-        assert_eq!(day.first_challenge(), "436");
-    }
-
-    #[test]
-    fn test_first_challenge_1() {
-        let input = "1,3,2";
-        let day = Day15::load(input);
-        assert_eq!(day.first_challenge(), "1");
-    }
-
-    #[test]
-    fn test_first_challenge_2() {
-        let input = "2,1,3";
-        let day = Day15::load(input);
-        assert_eq!(day.first_challenge(), "10");
-    }
-
-    #[test]
-    fn test_first_challenge_3() {
-        let input = "1,2,3";
-        let day = Day15::load(input);
-        assert_eq!(day.first_challenge(), "27");
-    }
-
-    #[test]
-    #[ignore]
-    fn test_second_challenge() {
-        let input = "0,3,6";
-        let day = Day15::load(input);
-        assert_eq!(day.second_challenge(), "175594");
-    }
-}
+/* examples */
+
+const EXAMPLES: &[Example] = &[
+    Example::first("0,3,6", "436"),
+    Example::first("1,3,2", "1"),
+    Example::first("2,1,3", "10"),
+    Example::first("1,2,3", "27"),
+    Example::second("0,3,6", "175594").slow(),
+];
+
+/* implementations */
+
+const IMPLEMENTATIONS: &[Implementation] = &[
+    Implementation::first("vector", |day| {
+        Ok(downcast::<Day15>(day).spoken_vec(2020)?.into())
+    }),
+    Implementation::second("vector", |day| {
+        Ok(downcast::<Day15>(day).spoken_vec(30000000)?.into())
+    }),
+];
+
+/* queries */
+
+// the vector takes a u32 per turn, so turns must fit in a u32 and the whole of it in 1GiB
+const MAX_TURN: usize = 1 << 28;
+
+const QUERIES: &[Query] = &[Query::new("spoken", "<turn>", |day, turn| {
+    let turn = turn.trim();
+    let turn = parse::number::<usize>(turn, turn)
+        .and_then(|n| match n {
+            0 => Err(parse::LineError::new(1, "turns start from 1")),
+            n if n > MAX_TURN => Err(parse::LineError::new(
+                1,
+                format!("turn too large, at most {}", MAX_TURN),
+            )),
+            n => Ok(n),
+        })
+        .map_err(|e| e.into_error(1))?;
+
+    Ok(downcast::<Day15>(day).spoken_vec(turn)?.into())
+})];
@@ -1,8 +1,12 @@
 use std::{collections::HashSet, ops::RangeInclusive};
 
-use itertools::Itertools;
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::{Error, Result};
 
-use super::Day;
+register_day!(16, "Ticket Translation", Day16, EXAMPLES);
 
 pub struct Day16 {
     rules: Vec<Rule>,
@@ -14,50 +18,67 @@ type Rule = (String, RangeInclusive<usize>, RangeInclusive<usize>);
 type Ticket = Vec<usize>;
 
 impl Day16 {
-    pub fn load(input: &str) -> Self {
-        let (rules, mine, others) = input.splitn(3, "\n\n").collect_tuple().unwrap();
+    pub fn load(input: &str) -> Result<Self> {
+        let sections = parse::sections(input.trim_end());
+        let (rules, mine, others) = match sections[..] {
+            [rules, mine, others] => (rules, mine, others),
+            _ => {
+                let line = input.lines().count() + 1;
+                let error = LineError::new(1, "expected rules, your ticket and nearby tickets");
+                return Err(error.into_error(line).into());
+            }
+        };
 
-        Self {
-            rules: Self::parse_rules(rules),
-            mine: Self::parse_tickets(mine)[0].to_vec(),
-            others: Self::parse_tickets(others),
-        }
-    }
+        let rules = parse::lines(rules.1, rules.0, Self::parse_rule)?;
 
-    fn parse_rules(s: &str) -> Vec<Rule> {
-        s.lines()
-            .map(|line| {
-                let (field, ranges) = line.splitn(2, ": ").collect_tuple().unwrap();
-                let (range1, range2) = ranges.splitn(2, " or ").collect_tuple().unwrap();
-
-                (
-                    field.to_string(),
-                    Self::parse_range(range1),
-                    Self::parse_range(range2),
-                )
-            })
-            .collect()
+        let mine = Self::parse_tickets(mine, None)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| LineError::new(1, "missing your ticket").into_error(mine.0 + 1))?;
+
+        let nearby = Self::parse_tickets(others, Some(mine.len()))?;
+
+        Ok(Self {
+            rules,
+            mine,
+            others: nearby,
+        })
     }
 
-    fn parse_range(s: &str) -> RangeInclusive<usize> {
-        let (start, stop) = s
-            .splitn(2, "-")
-            .map(|num| num.parse::<usize>().unwrap())
-            .collect_tuple()
-            .unwrap();
+    fn parse_rule(line: &str) -> LineResult<Rule> {
+        let (field, ranges) = parse::split_pair(line, line, ": ")?;
+        let (range1, range2) = parse::split_pair(line, ranges, " or ")?;
 
-        start..=stop
+        Ok((
+            field.to_string(),
+            Self::parse_range(line, range1)?,
+            Self::parse_range(line, range2)?,
+        ))
     }
 
-    fn parse_tickets(s: &str) -> Vec<Ticket> {
-        s.lines()
-            .skip(1)
-            .map(|line| {
-                line.split(',')
-                    .map(|num| num.parse::<usize>().unwrap())
-                    .collect()
-            })
-            .collect()
+    fn parse_range(line: &str, s: &str) -> LineResult<RangeInclusive<usize>> {
+        let (start, stop) = parse::split_pair(line, s, "-")?;
+        Ok(parse::number(line, start)?..=parse::number(line, stop)?)
+    }
+
+    // skip the "your ticket:" / "nearby tickets:" header,
+    // every ticket must have `fields` fields when given
+    fn parse_tickets((first_line, s): (usize, &str), fields: Option<usize>) -> Result<Vec<Ticket>> {
+        let body = s.split_once('\n').map(|(_, body)| body).unwrap_or_default();
+        parse::lines(body, first_line + 1, |line| {
+            let ticket = line
+                .split(',')
+                .map(|num| parse::number(line, num))
+                .collect::<LineResult<Vec<_>>>()?;
+
+            match fields {
+                Some(fields) if fields != ticket.len() => Err(LineError::new(
+                    1,
+                    format!("expected {} fields, found {}", fields, ticket.len()),
+                )),
+                _ => Ok(ticket),
+            }
+        })
     }
 
     fn is_valid(&self, field: &usize) -> bool {
@@ -70,16 +91,18 @@ impl Day16 {
 }
 
 impl Day for Day16 {
-    fn first_challenge(&self) -> String {
-        self.others
+    fn first_challenge(&self) -> Result<Answer> {
+        let error_rate = self
+            .others
             .iter()
             .flatten()
             .filter(|&field| !self.is_valid(field))
-            .sum::<usize>()
-            .to_string()
+            .sum::<usize>();
+
+        Ok(error_rate.into())
     }
 
-    fn second_challenge(&self) -> String {
+    fn second_challenge(&self) -> Result<Answer> {
         // consider only valid tickets
         let valid_others = self
             .others
@@ -103,9 +126,9 @@ impl Day for Day16 {
                     .map(|&rule| rule.clone())
                     .collect::<Vec<_>>();
 
-                mismatched
-                    .iter()
-                    .for_each(|rule| drop(constraints[i].remove(rule)));
+                for rule in mismatched.iter() {
+                    constraints[i].remove(rule);
+                }
             }
         }
 
@@ -125,39 +148,44 @@ impl Day for Day16 {
             let unique = fields
                 .iter()
                 .filter(|f| f.len() == 1)
-                .map(|f| f.iter().next().unwrap().to_string())
+                .flat_map(|f| f.iter().map(|name| name.to_string()))
                 .collect::<Vec<_>>();
 
-            fields
-                .iter_mut()
-                .filter(|f| f.len() > 1)
-                .for_each(|f| unique.iter().for_each(|name| drop(f.remove(name))));
+            let before = fields.iter().map(|f| f.len()).sum::<usize>();
+
+            for f in fields.iter_mut().filter(|f| f.len() > 1) {
+                for name in unique.iter() {
+                    f.remove(name);
+                }
+            }
+
+            // no way to narrow it down any further
+            if fields.iter().map(|f| f.len()).sum::<usize>() == before {
+                return Err(Error::NotFound);
+            }
         }
 
-        // filter departure fields
-        let departure_fields = fields
+        // one name per field
+        let names = fields
             .iter()
-            .map(|f| f.iter().next().unwrap())
-            .enumerate()
-            .filter(|(_, name)| name.starts_with("departure"));
+            .map(|f| f.iter().next().ok_or(Error::NotFound))
+            .collect::<Result<Vec<_>>>()?;
 
-        // compute result
-        departure_fields
+        // filter departure fields and compute result
+        let result = names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| name.starts_with("departure"))
             .map(|(i, _)| self.mine[i])
-            .fold(1, |acc, field| acc * field)
-            .to_string()
+            .product::<usize>();
+
+        Ok(result.into())
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    #[test]
-    fn test_first_challenge() {
-        let input = "class: 1-3 or 5-7
+const INPUT_1: &str = "class: 1-3 or 5-7
 row: 6-11 or 33-44
 seat: 13-40 or 45-50
 
@@ -169,13 +197,8 @@ nearby tickets:
 40,4,50
 55,2,20
 38,6,12";
-        let day = Day16::load(input);
-        assert_eq!(day.first_challenge(), "71");
-    }
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "class: 0-1 or 4-19
+const INPUT_2: &str = "class: 0-1 or 4-19
 departure row: 0-5 or 8-19
 departure seat: 0-13 or 16-19
 
@@ -186,7 +209,8 @@ nearby tickets:
 3,9,18
 15,1,5
 5,14,9";
-        let day = Day16::load(input);
-        assert_eq!(day.second_challenge(), "143");
-    }
-}
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "71"),
+    Example::second(INPUT_2, "143"),
+];
@@ -1,143 +1,129 @@
-use std::cmp::min;
+use std::collections::HashSet;
 
 use itertools::Itertools;
 
-use super::Day;
+use super::{automaton, parse, Answer, Day, Example, Part};
+use crate::{error::Result, frame::Frame};
+
+register_day!(17, "Conway Cubes", Day17, EXAMPLES);
 
 pub struct Day17 {
     input: Plane<Cube>,
 }
 
 impl Day17 {
-    pub fn load(input: &str) -> Self {
-        Self {
-            input: Self::parse_input(input),
-        }
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: Self::parse_input(input)?,
+        })
     }
 
-    fn parse_input(s: &str) -> Plane<Cube> {
-        s.lines()
-            .map(|l| l.chars().map(Self::parse_cube).collect::<Vec<_>>())
-            .collect::<Vec<_>>()
+    fn parse_input(s: &str) -> Result<Plane<Cube>> {
+        parse::grid(s, 1, Self::parse_cube)
     }
 
-    fn parse_cube(c: char) -> Cube {
+    fn parse_cube(c: char) -> Option<Cube> {
         match c {
-            '#' => Cube::Active,
-            '.' => Cube::Inactive,
-            _ => panic!("Invalid cube!"),
+            '#' => Some(Cube::Active),
+            '.' => Some(Cube::Inactive),
+            _ => None,
         }
     }
 
-    fn run(&self, iter: usize, enable_4d: bool) -> HyperSpace<Cube> {
-        let expansion = 2 * iter;
-
-        let size = (
-            1 + if enable_4d { expansion } else { 0 },
-            1 + expansion,
-            self.input.len() + expansion,
-            self.input[0].len() + expansion,
-        );
+    // the active cubes after `iter` cycles, the input is the plane w = z = 0
+    fn run(&self, iter: usize, enable_4d: bool) -> Result<HashSet<Coord>> {
+        let mut state = self
+            .input
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, &cube)| (i, j, cube)))
+            .filter(|&(_, _, cube)| cube == Cube::Active)
+            .map(|(i, j, _)| [0, 0, i as i32, j as i32])
+            .collect::<HashSet<_>>();
+
+        // 26 neighbours in three dimensions, 80 in four
+        let offsets = (0..4)
+            .map(|_| -1..=1)
+            .multi_cartesian_product()
+            .filter(|d| d.iter().any(|&x| x != 0) && (enable_4d || d[0] == 0))
+            .collect::<Vec<_>>();
 
-        let mut state = HyperSpace::new(Cube::Inactive, size);
+        let neighbours = |coord: Coord| {
+            offsets.iter().map(move |d| {
+                let mut neighbour = coord;
+                neighbour.iter_mut().zip(d).for_each(|(x, dx)| *x += dx);
+                neighbour
+            })
+        };
 
-        // initialize middle plane
-        for i in 0..self.input.len() {
-            for j in 0..self.input[0].len() {
-                let coord = (size.0 / 2, size.1 / 2, iter + i, iter + j);
-                state.set(coord, self.input[i][j]);
-            }
-        }
-
-        // run the simulation
         for _ in 0..iter {
-            let active = &Self::count_active_adjacent(&state);
-            Self::update(&mut state, active);
+            state = automaton::step(&state, neighbours, Self::stays_active)?;
         }
 
-        state
+        Ok(state)
     }
 
-    fn update(state: &mut HyperSpace<Cube>, active: &HyperSpace<usize>) {
-        let to_update = state
-            .coords
-            .iter()
-            .filter(|&coord| Self::should_update_cube(state.get(*coord), active.get(*coord)))
-            .cloned()
-            .collect::<Vec<_>>();
+    fn stays_active(active: bool, neighbours: usize) -> bool {
+        match (active, neighbours) {
+            (true, 2) | (true, 3) => true,
+            (true, _) => false,
 
-        to_update
-            .into_iter()
-            .for_each(|coord| match state.get(coord) {
-                Cube::Active => state.set(coord, Cube::Inactive),
-                Cube::Inactive => state.set(coord, Cube::Active),
-            });
+            (false, 3) => true,
+            (false, _) => false,
+        }
     }
+}
 
-    fn should_update_cube(cube: Cube, active: usize) -> bool {
-        match (cube, active) {
-            (Cube::Active, 2) => false,
-            (Cube::Active, 3) => false,
-            (Cube::Active, _) => true,
-
-            (Cube::Inactive, 3) => true,
-            (Cube::Inactive, _) => false,
-        }
+impl Day for Day17 {
+    fn first_challenge(&self) -> Result<Answer> {
+        Ok(self.run(6, false)?.len().into())
     }
 
-    fn count_active_adjacent(state: &HyperSpace<Cube>) -> HyperSpace<usize> {
-        let mut active = HyperSpace::new(0, state.size);
-        for &coord in state.coords.iter() {
-            active.set(coord, Self::count_active_adjacent_cube(state, coord));
-        }
-        active
+    fn second_challenge(&self) -> Result<Answer> {
+        Ok(self.run(6, true)?.len().into())
     }
 
-    fn count_active_adjacent_cube(state: &HyperSpace<Cube>, (t, k, i, j): Coord) -> usize {
-        let range_t = t.saturating_sub(1)..min(t + 2, state.size.0);
-        let range_k = k.saturating_sub(1)..min(k + 2, state.size.1);
-        let range_i = i.saturating_sub(1)..min(i + 2, state.size.2);
-        let range_j = j.saturating_sub(1)..min(j + 2, state.size.3);
-
-        let mut res = 0;
-
-        for tt in range_t {
-            for kk in range_k.clone() {
-                for ii in range_i.clone() {
-                    for jj in range_j.clone() {
-                        if state.get((tt, kk, ii, jj)) == Cube::Active {
-                            res += 1;
-                        }
-                    }
-                }
-            }
-        }
+    // the layers with any active cube after each cycle, just like the puzzle text
+    fn visualize(&self, part: Part) -> Result<Vec<Frame>> {
+        let enable_4d = part == Part::Second;
 
-        if state.get((t, k, i, j)) == Cube::Active {
-            res -= 1;
-        }
+        let frames = (0..=6)
+            .map(|cycle| {
+                let state = self.run(cycle, enable_4d)?;
 
-        res
-    }
+                // cubes can spread by one per cycle in every direction
+                let spread = cycle as i32;
+                let range_w = if enable_4d { -spread..=spread } else { 0..=0 };
+                let range_i = -spread..self.input.len() as i32 + spread;
+                let range_j = -spread..self.input[0].len() as i32 + spread;
 
-    fn count_active(state: &HyperSpace<Cube>) -> usize {
-        state
-            .coords
-            .iter()
-            .filter(|&coord| state.get(*coord) == Cube::Active)
-            .count()
-    }
-}
+                let mut lines = vec![format!("after {} cycles", cycle)];
+                for (w, z) in range_w.cartesian_product(-spread..=spread) {
+                    if !state.iter().any(|coord| coord[0] == w && coord[1] == z) {
+                        continue;
+                    }
 
-impl Day for Day17 {
-    fn first_challenge(&self) -> String {
-        let state = &self.run(6, false);
-        Self::count_active(state).to_string()
-    }
+                    lines.push(String::new());
+                    lines.push(match enable_4d {
+                        true => format!("z={}, w={}", z, w),
+                        false => format!("z={}", z),
+                    });
+                    lines.extend(range_i.clone().map(|i| {
+                        range_j
+                            .clone()
+                            .map(|j| match state.contains(&[w, z, i, j]) {
+                                true => '#',
+                                false => '.',
+                            })
+                            .collect::<String>()
+                    }));
+                }
+
+                Ok(Frame::Text(lines))
+            })
+            .collect::<Result<_>>()?;
 
-    fn second_challenge(&self) -> String {
-        let state = &self.run(6, true);
-        Self::count_active(state).to_string()
+        Ok(frames)
     }
 }
 
@@ -147,72 +133,18 @@ enum Cube {
     Active,
 }
 
-type Coord = (usize, usize, usize, usize);
-type Size = (usize, usize, usize, usize);
+// w, z, x, y
+type Coord = [i32; 4];
 
 type Plane<T> = Vec<Vec<T>>;
-type Space<T> = Vec<Plane<T>>;
-
-struct HyperSpace<T> {
-    space: Vec<Space<T>>,
-    coords: Vec<Coord>,
-    size: Size,
-}
-
-impl<T> HyperSpace<T>
-where
-    T: Clone + Copy,
-{
-    fn new(value: T, (wsize, zsize, xsize, ysize): Size) -> HyperSpace<T> {
-        let space = vec![vec![vec![vec![value; ysize]; xsize]; zsize]; wsize];
-
-        let coords = (0..wsize)
-            .cartesian_product(0..zsize)
-            .cartesian_product(0..xsize)
-            .cartesian_product(0..ysize)
-            .map(|(((t, k), i), j)| (t, k, i, j))
-            .collect();
-
-        let size = (wsize, zsize, xsize, ysize);
-
-        Self {
-            space,
-            coords,
-            size,
-        }
-    }
 
-    fn get(&self, (t, k, i, j): Coord) -> T {
-        self.space[t][k][i][j]
-    }
-
-    fn set(&mut self, (t, k, i, j): Coord, value: T) {
-        self.space[t][k][i][j] = value
-    }
-}
-
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    #[test]
-    fn test_first_challenge() {
-        let input = ".#.
+const INPUT: &str = ".#.
 ..#
 ###";
-        let day = Day17::load(input);
-        assert_eq!(day.first_challenge(), "112");
-    }
 
-    #[test]
-    #[ignore]
-    fn test_second_challenge() {
-        let input = ".#.
-..#
-###";
-        let day = Day17::load(input);
-        assert_eq!(day.second_challenge(), "848");
-    }
-}
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "112"),
+    Example::second(INPUT, "848"),
+];
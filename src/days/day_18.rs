@@ -1,17 +1,63 @@
-use super::Day;
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::Result;
+
+register_day!(18, "Operation Order", Day18, EXAMPLES);
 
 pub struct Day18 {
     input: Vec<String>,
 }
 
 impl Day18 {
-    pub fn load(input: &str) -> Self {
-        let input = input.lines().map(|e| e.to_string()).collect::<Vec<_>>();
+    pub fn load(input: &str) -> Result<Self> {
+        let input = parse::lines(input, 1, |line| {
+            Self::validate(line)?;
+            Ok(line.to_string())
+        })?;
+
+        Ok(Self { input })
+    }
+
+    // the solver is a bit naive: better check that it receives well formed expressions
+    fn validate(s: &str) -> LineResult<()> {
+        let mut expect_operand = true;
+        let mut depth = 0;
+
+        for (j, c) in s.char_indices() {
+            let ok = match c {
+                ' ' => true,
+                '0'..='9' | '(' if expect_operand => {
+                    expect_operand = c == '(';
+                    depth += (c == '(') as usize;
+                    true
+                }
+                '+' | '*' if !expect_operand => {
+                    expect_operand = true;
+                    true
+                }
+                ')' if !expect_operand && depth > 0 => {
+                    depth -= 1;
+                    true
+                }
+                '0'..='9' | '(' | ')' | '+' | '*' => false,
+                _ => return Err(LineError::new(j + 1, parse::invalid_char(c))),
+            };
 
-        Self { input }
+            if !ok {
+                return Err(LineError::new(j + 1, format!("unexpected '{}'", c)));
+            }
+        }
+
+        match (expect_operand, depth) {
+            (true, _) => Err(LineError::new(s.len() + 1, "missing operand")),
+            (_, 0) => Ok(()),
+            _ => Err(LineError::new(s.len() + 1, "missing ')'")),
+        }
     }
 
-    fn solve<T>(expression: &String) -> usize
+    fn solve<T>(expression: &str) -> usize
     where
         T: OpPriority,
     {
@@ -19,7 +65,7 @@ impl Day18 {
         val
     }
 
-    fn solve_rec<T>(expression: &String, index: usize) -> (usize, usize)
+    fn solve_rec<T>(expression: &str, index: usize) -> (usize, usize)
     where
         T: OpPriority,
     {
@@ -61,14 +107,14 @@ impl Day18 {
 
         match stack.pop().unwrap() {
             Parse::Done(expr) => (expr.value(), delta - 1),
-            _ => panic!("aaaah!"),
+            _ => unreachable!("aaaah! expressions are validated while loading"),
         }
     }
 
     fn parse_close(stack: &mut Vec<Parse>) {
         let mut expr = match stack.pop().unwrap() {
             Parse::Done(expr) => expr,
-            _ => panic!("aaaah!"),
+            _ => unreachable!("aaaah! expressions are validated while loading"),
         };
 
         while let Parse::Partial(op, left) = stack.pop().unwrap() {
@@ -90,7 +136,7 @@ impl Day18 {
 
             Parse::Done(left) => Parse::Partial(op, left),
 
-            _ => panic!("aaaah!"),
+            _ => unreachable!("aaaah! expressions are validated while loading"),
         };
         stack.push(parse);
     }
@@ -104,27 +150,31 @@ impl Day18 {
 
             Parse::Partial(op, left) => Expr::Op(op, Box::new(left), Box::new(expr)),
 
-            _ => panic!("aaaah!"),
+            _ => unreachable!("aaaah! expressions are validated while loading"),
         };
         stack.push(Parse::Done(expr));
     }
 }
 
 impl Day for Day18 {
-    fn first_challenge(&self) -> String {
-        self.input
+    fn first_challenge(&self) -> Result<Answer> {
+        let sum = self
+            .input
             .iter()
             .map(|s| Self::solve::<SamePriority>(s))
-            .sum::<usize>()
-            .to_string()
+            .sum::<usize>();
+
+        Ok(sum.into())
     }
 
-    fn second_challenge(&self) -> String {
-        self.input
+    fn second_challenge(&self) -> Result<Answer> {
+        let sum = self
+            .input
             .iter()
             .map(|s| Self::solve::<AddFirst>(s))
-            .sum::<usize>()
-            .to_string()
+            .sum::<usize>();
+
+        Ok(sum.into())
     }
 }
 
@@ -191,6 +241,18 @@ impl OpPriority for AddFirst {
     }
 }
 
+/* examples */
+
+const INPUT: &str = "2 * 3 + (4 * 5)
+5 + (8 * 3 + 9 + 3 * 4 * 3)
+5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))
+((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "26335"),
+    Example::second(INPUT, "693891"),
+];
+
 /* tests */
 
 #[cfg(test)]
@@ -198,28 +260,14 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_first_challenge() {
+    fn test_invalid_expression() {
         let input = "2 * 3 + (4 * 5)
-5 + (8 * 3 + 9 + 3 * 4 * 3)
-5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))
-((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
-        let day = Day18::load(input);
-        assert_eq!(
-            day.first_challenge(),
-            (26 + 437 + 12240 + 13632).to_string()
-        );
-    }
+5 + * 3
+(2 + 3";
+        let error = Day18::load(input).err().unwrap();
+        assert_eq!(error.to_string(), "parse error at 2:5: unexpected '*'");
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "2 * 3 + (4 * 5)
-5 + (8 * 3 + 9 + 3 * 4 * 3)
-5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))
-((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
-        let day = Day18::load(input);
-        assert_eq!(
-            day.second_challenge(),
-            (46 + 1445 + 669060 + 23340).to_string()
-        );
+        let error = Day18::load("(2 + 3").err().unwrap();
+        assert_eq!(error.to_string(), "parse error at 1:7: missing ')'");
     }
 }
@@ -3,7 +3,16 @@ use std::collections::HashMap;
 use itertools::Itertools;
 use regex::Regex;
 
-use super::Day;
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::{
+    cancel,
+    error::{Error, Result},
+};
+
+register_day!(19, "Monster Messages", Day19, EXAMPLES);
 
 #[derive(Clone)]
 pub struct Day19 {
@@ -21,43 +30,45 @@ enum Rule {
 }
 
 impl Day19 {
-    pub fn load(input: &str) -> Self {
-        let (rules, messages) = input.splitn(2, "\n\n").collect_tuple().unwrap();
+    pub fn load(input: &str) -> Result<Self> {
+        let (rules, messages) = input.split_once("\n\n").unwrap_or((input, ""));
 
-        Self {
-            rules: Self::parse_rules(rules),
+        Ok(Self {
+            rules: Self::parse_rules(rules)?,
             messages: Self::parse_messages(messages),
-        }
+        })
     }
 
-    fn parse_rules(s: &str) -> HashMap<usize, Rule> {
-        s.lines()
-            .map(|l| {
-                let (index, rule) = l.splitn(2, ": ").collect_tuple().unwrap();
-                let index = index.parse::<usize>().unwrap();
-                let rule = Self::parse_rule(rule);
-                (index, rule)
-            })
-            .collect::<HashMap<_, _>>()
+    fn parse_rules(s: &str) -> Result<HashMap<usize, Rule>> {
+        let rules = parse::lines(s, 1, |l| {
+            let (index, rule) = parse::split_pair(l, l, ": ")?;
+            Ok((parse::number(l, index)?, Self::parse_rule(l, rule)?))
+        })?;
+
+        Ok(rules.into_iter().collect())
     }
 
-    fn parse_rule(s: &str) -> Rule {
-        if s.contains("\"") {
-            return Rule::Char(s.chars().nth(1).unwrap());
+    // `s` is a slice of `line`
+    fn parse_rule(line: &str, s: &str) -> LineResult<Rule> {
+        if s.contains('"') {
+            return match s.chars().collect_vec()[..] {
+                ['"', c, '"'] => Ok(Rule::Char(c)),
+                _ => Err(LineError::at(line, s, "expected a single quoted character")),
+            };
         }
 
-        if s.contains("|") {
-            let (left, right) = s.splitn(2, " | ").collect_tuple().unwrap();
-            return Rule::Or(Self::parse_nums(left), Self::parse_nums(right));
+        if let Some((left, right)) = s.split_once(" | ") {
+            return Ok(Rule::Or(
+                Self::parse_nums(line, left)?,
+                Self::parse_nums(line, right)?,
+            ));
         }
 
-        Rule::And(Self::parse_nums(s))
+        Ok(Rule::And(Self::parse_nums(line, s)?))
     }
 
-    fn parse_nums(s: &str) -> Vec<usize> {
-        s.split(" ")
-            .map(|num| num.parse::<usize>().unwrap())
-            .collect_vec()
+    fn parse_nums(line: &str, s: &str) -> LineResult<Vec<usize>> {
+        s.split(' ').map(|num| parse::number(line, num)).collect()
     }
 
     fn parse_messages(s: &str) -> Vec<String> {
@@ -66,96 +77,95 @@ impl Day19 {
 }
 
 impl Day for Day19 {
-    fn first_challenge(&self) -> String {
-        let regex = build_regex(&self.rules);
+    fn first_challenge(&self) -> Result<Answer> {
+        let regex = build_regex(&self.rules)?;
 
-        self.messages
+        let count = self
+            .messages
             .iter()
             .filter(|&mex| regex.is_match(mex))
-            .count()
-            .to_string()
+            .count();
+
+        Ok(count.into())
     }
 
     // there are bonus points for creativity, right? :D
-    fn second_challenge(&self) -> String {
+    fn second_challenge(&self) -> Result<Answer> {
         let mut rules = self.rules.clone();
         rules.insert(8, Rule::Plus(42));
 
         // if you can't count to infinity, at which number should you stop?
-        let (count, _) = (1..=42).fold((0, self.messages.clone()), |(count, ko), n| {
+        let (count, _) = (1..=42).try_fold((0, self.messages.clone()), |(count, ko), n| {
+            cancel::check()?;
             rules.insert(11, Rule::Nested((42, 31), n));
 
-            let regex = build_regex(&rules);
+            let regex = build_regex(&rules)?;
 
             let (ok, ko) = ko
                 .into_iter()
                 .partition::<Vec<String>, _>(|mex| regex.is_match(mex));
 
-            (count + ok.len(), ko)
-        });
+            Ok::<_, Error>((count + ok.len(), ko))
+        })?;
 
-        count.to_string()
+        Ok(count.into())
     }
 }
 
-fn build_regex(rules: &HashMap<usize, Rule>) -> Regex {
-    let regex = build_regex_string(rules, 0, &mut HashMap::new());
-    Regex::new(format!("^{}$", regex).as_str()).unwrap()
+// rules may reference each other before the second challenge patches them, so check only now
+fn build_regex(rules: &HashMap<usize, Rule>) -> Result<Regex> {
+    let regex = build_regex_string(rules, 0, &mut HashMap::new())?;
+    Ok(Regex::new(format!("^{}$", regex).as_str()).unwrap())
 }
 
 fn build_regex_string(
     rules: &HashMap<usize, Rule>,
     index: usize,
     memo: &mut HashMap<usize, String>,
-) -> String {
+) -> Result<String> {
     if let Some(regex) = memo.get(&index) {
-        return regex.to_string();
+        return Ok(regex.to_string());
     }
 
-    let regex = match rules.get(&index).unwrap() {
-        Rule::Char(char) => char.to_string(),
+    let regex = match rules.get(&index).ok_or(Error::NotFound)? {
+        Rule::Char(char) => regex::escape(&char.to_string()),
 
-        Rule::And(indexes) => format!("{}", build_regex_and(rules, indexes, memo)),
+        Rule::And(indexes) => build_regex_and(rules, indexes, memo)?,
 
         Rule::Or(left, right) => format!(
             "({}|{})",
-            build_regex_and(rules, left, memo),
-            build_regex_and(rules, right, memo)
+            build_regex_and(rules, left, memo)?,
+            build_regex_and(rules, right, memo)?
         ),
 
-        &Rule::Plus(index) => format!("{}+", build_regex_string(rules, index, memo)),
+        &Rule::Plus(index) => format!("{}+", build_regex_string(rules, index, memo)?),
 
         &Rule::Nested((left, right), n) => {
-            let left = build_regex_string(rules, left, memo);
-            let right = build_regex_string(rules, right, memo);
+            let left = build_regex_string(rules, left, memo)?;
+            let right = build_regex_string(rules, right, memo)?;
             format!("({}{{{}}}{}{{{}}})", left, n, right, n)
         }
     };
 
     memo.insert(index, regex.to_string());
-    regex
+    Ok(regex)
 }
 
 fn build_regex_and(
     rules: &HashMap<usize, Rule>,
     indexes: &[usize],
     memo: &mut HashMap<usize, String>,
-) -> String {
+) -> Result<String> {
     indexes
         .iter()
-        .map(|&i| build_regex_string(rules, i, memo).to_string())
-        .join("")
+        .map(|&i| build_regex_string(rules, i, memo))
+        .collect::<Result<Vec<_>>>()
+        .map(|regexes| regexes.join(""))
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/* examples */
 
-    #[test]
-    fn test_first_challenge() {
-        let input = "0: 4 1 5
+const INPUT_1: &str = "0: 4 1 5
 1: 2 3 | 3 2
 2: 4 4 | 5 5
 3: 4 5 | 5 4
@@ -167,13 +177,8 @@ bababa
 abbbab
 aaabbb
 aaaabbb";
-        let day = Day19::load(input);
-        assert_eq!(day.first_challenge(), "2");
-    }
 
-    #[test]
-    fn test_second_challenge() {
-        let input = "42: 9 14 | 10 1
+const INPUT_2: &str = "42: 9 14 | 10 1
 9: 14 27 | 1 26
 10: 23 14 | 28 1
 1: \"a\"
@@ -220,13 +225,8 @@ aaaabbaaaabbaaa
 aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
 babaaabbbaaabaababbaabababaaab
 aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba";
-        let day = Day19::load(input);
-        assert_eq!(day.second_challenge(), "12");
-    }
 
-    #[test]
-    fn test_second_challenge_plus() {
-        let input = "0: 8
+const INPUT_3: &str = "0: 8
 1: \"a\"
 2: \"b\"
 8: 1
@@ -240,13 +240,8 @@ ab
 ba
 abababab
 aaaabbbb";
-        let day = Day19::load(input);
-        assert_eq!(day.second_challenge(), "2");
-    }
 
-    #[test]
-    fn test_second_challenge_nested() {
-        let input = "0: 11
+const INPUT_4: &str = "0: 11
 1: \"a\"
 2: \"b\"
 42: 1
@@ -265,7 +260,10 @@ bbbbbbb
 ba
 bbaa
 abababab";
-        let day = Day19::load(input);
-        assert_eq!(day.second_challenge(), "3");
-    }
-}
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "2"),
+    Example::second(INPUT_2, "12"),
+    Example::second(INPUT_3, "2"),
+    Example::second(INPUT_4, "3"),
+];
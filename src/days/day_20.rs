@@ -1,22 +1,76 @@
 use itertools::Itertools;
 
-use super::Day;
+use super::{
+    parse::{self, LineError},
+    Answer, Day, Example, Part,
+};
+use crate::{
+    error::{Error, Result},
+    frame::Frame,
+};
+
+register_day!(20, "Jurassic Jigsaw", Day20, EXAMPLES);
 
 pub struct Day20 {
     input: Vec<Tile>,
 }
 
 impl Day20 {
-    pub fn load(input: &str) -> Self {
-        let input = input.split("\n\n").map(Tile::from_str).collect::<Vec<_>>();
+    pub fn load(input: &str) -> Result<Self> {
+        let tiles = parse::all(
+            parse::sections(input.trim_end())
+                .into_iter()
+                .map(|(first_line, section)| Ok((first_line, Tile::parse(section, first_line)?))),
+        )?;
+
+        // tiles must share the same size to fit together. There is always a section to parse,
+        // so no tile at all means that every one of them was skipped while checking
+        let size = match tiles.first() {
+            Some((_, tile)) => tile.pixels.len(),
+            None => return Ok(Self { input: vec![] }),
+        };
+
+        let input = parse::all(tiles.into_iter().map(|(first_line, tile)| {
+            if tile.pixels.len() == size {
+                return Ok(tile);
+            }
+
+            let message = format!("expected {}x{} tile", size, size);
+            Err(LineError::new(1, message).into_error(first_line).into())
+        }))?;
 
-        Self { input }
+        Ok(Self { input })
+    }
+
+    // the whole image, turned so that the dragons can be seen, and where they are
+    fn find_dragons(&self) -> Result<(Tile, Vec<(usize, usize)>)> {
+        let solution = part_1::rearrange(&self.input)?.ok_or(Error::NotFound)?;
+        let merged = part_2::merge(&solution);
+
+        let found = merged
+            .generate_combinations()
+            .into_iter()
+            .map(|tile| {
+                let dragons = part_2::find_dragons(&tile);
+                (tile, dragons)
+            })
+            .max_by_key(|(_, dragons)| dragons.len())
+            .unwrap();
+
+        Ok(found)
+    }
+
+    fn draw(pixels: MatrixSlice<bool>) -> Vec<String> {
+        pixels
+            .iter()
+            .map(|row| row.iter().map(|&on| if on { '#' } else { '.' }).collect())
+            .collect()
     }
 }
 
 impl Day for Day20 {
-    fn first_challenge(&self) -> String {
-        let solution = part_1::rearrange(&self.input);
+    fn first_challenge(&self) -> Result<Answer> {
+        let solution = part_1::rearrange(&self.input)?.ok_or(Error::NotFound)?;
         let size = solution.len();
 
         let mul_corners = solution[0][0].id
@@ -24,19 +78,12 @@ impl Day for Day20 {
             * solution[size - 1][size - 1].id
             * solution[size - 1][0].id;
 
-        mul_corners.to_string()
+        Ok(mul_corners.into())
     }
 
-    fn second_challenge(&self) -> String {
-        let solution = part_1::rearrange(&self.input);
-        let merged = part_2::merge(&solution);
-        let combinations = merged.generate_combinations();
-
-        let (tile, dragons) = combinations
-            .iter()
-            .map(|tile| (tile, part_2::count_dragons(tile)))
-            .max_by_key(|(_, count)| *count)
-            .unwrap();
+    fn second_challenge(&self) -> Result<Answer> {
+        let (tile, dragons) = self.find_dragons()?;
+        let dragons = dragons.len();
 
         let all_rough = tile
             .pixels
@@ -44,14 +91,55 @@ impl Day for Day20 {
             .map(|row| row.iter().filter(|&pixel| *pixel).count())
             .sum::<usize>();
 
-        let dragon_rough = (&*part_2::DRAGON)
-            .iter()
-            .map(|row| row.len())
-            .sum::<usize>();
+        let dragon_rough = part_2::DRAGON.iter().map(|row| row.len()).sum::<usize>();
 
         let not_dragon = all_rough - dragons * dragon_rough;
 
-        not_dragon.to_string()
+        Ok(not_dragon.into())
+    }
+
+    // the first part shows the tiles in place, the second one the dragons among the waves
+    fn visualize(&self, part: Part) -> Result<Vec<Frame>> {
+        let frame = match part {
+            Part::First => {
+                let solution = part_1::rearrange(&self.input)?.ok_or(Error::NotFound)?;
+                let mut lines = Vec::new();
+
+                for row in solution.iter() {
+                    lines.push(row.iter().map(|tile| format!("{:<11}", tile.id)).join(""));
+                    let drawn = row.iter().map(|tile| Self::draw(&tile.pixels)).collect_vec();
+                    for i in 0..drawn[0].len() {
+                        lines.push(drawn.iter().map(|tile| format!("{} ", tile[i])).join(""));
+                    }
+                    lines.push(String::new());
+                }
+
+                lines
+            }
+
+            Part::Second => {
+                let (tile, dragons) = self.find_dragons()?;
+                let mut lines = Self::draw(&tile.pixels)
+                    .into_iter()
+                    .map(|line| line.into_bytes())
+                    .collect_vec();
+
+                for (i, offset) in dragons {
+                    for (di, row) in part_2::DRAGON.iter().enumerate() {
+                        for &dj in row {
+                            lines[i + di][offset + dj] = b'O';
+                        }
+                    }
+                }
+
+                lines
+                    .into_iter()
+                    .map(|line| String::from_utf8(line).expect("aaaah! only ascii here"))
+                    .collect()
+            }
+        };
+
+        Ok(vec![Frame::Text(frame)])
     }
 }
 
@@ -67,16 +155,38 @@ struct Tile {
 }
 
 impl Tile {
-    fn from_str(s: &str) -> Tile {
-        let mut lines = s.lines();
-
-        let id = lines.next().unwrap()[5..9].parse::<usize>().unwrap();
+    fn parse(s: &str, first_line: usize) -> Result<Tile> {
+        let (header, pixels) = s.split_once('\n').unwrap_or((s, ""));
+
+        let id = header
+            .strip_prefix("Tile ")
+            .and_then(|id| id.strip_suffix(':'))
+            .ok_or_else(|| LineError::new(1, "expected 'Tile <id>:'"))
+            .and_then(|id| parse::number(header, id))
+            .map_err(|e| e.into_error(first_line))?;
+
+        let pixels = parse::grid(pixels, first_line + 1, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+
+        if pixels.len() != pixels[0].len() {
+            let message = format!(
+                "expected square tile, found {}x{}",
+                pixels.len(),
+                pixels[0].len()
+            );
+            return Err(LineError::new(1, message).into_error(first_line).into());
+        }
 
-        let pixels = lines
-            .map(|l| l.chars().map(|c| c == '#').collect::<Vec<_>>())
-            .collect::<Vec<_>>();
+        // nothing would be left of the image without the borders
+        if pixels.len() < 3 {
+            let message = format!("expected at least 3x3 tile, found {0}x{0}", pixels.len());
+            return Err(LineError::new(1, message).into_error(first_line).into());
+        }
 
-        Self { id, pixels }
+        Ok(Self { id, pixels })
     }
 
     fn rotate(&self) -> Self {
@@ -129,6 +239,7 @@ mod part_1 {
     use num::integer::Roots;
 
     use super::{Id, Matrix, MatrixSlice, MatrixSliceMut, Tile};
+    use crate::{cancel, error::Result};
 
     type Edge = usize;
     type EdgeIndex = HashMap<Edge, HashSet<IdComb>>;
@@ -168,7 +279,13 @@ mod part_1 {
         Left,
     }
 
-    pub(super) fn rearrange(tiles: &[Tile]) -> Matrix<Tile> {
+    pub(super) fn rearrange(tiles: &[Tile]) -> Result<Option<Matrix<Tile>>> {
+        // the image must be a square
+        let size = tiles.len().sqrt();
+        if size * size != tiles.len() {
+            return Ok(None);
+        }
+
         // all orientations (combinations) by tile id
         let combinations_by_id = group_combinations_by_id(tiles);
 
@@ -184,7 +301,6 @@ mod part_1 {
         };
 
         // backtrack
-        let size = tiles.len().sqrt();
         let empty_solution = &mut vec![vec![(0, 0); size]; size];
         let used = &mut HashSet::new();
         let solution = backtrack(
@@ -194,18 +310,23 @@ mod part_1 {
             &combinations_by_id,
             &outlines_by_id,
             &outline_index,
-        )
-        .unwrap();
+        )?;
+        let solution = match solution {
+            Some(solution) => solution,
+            None => return Ok(None),
+        };
 
         // build rearrenged tiles
-        solution
+        let tiles = solution
             .iter()
             .map(|row| {
                 row.iter()
                     .map(|(id, comb)| combinations_by_id.get(id).unwrap()[*comb].clone())
                     .collect_vec()
             })
-            .collect_vec()
+            .collect_vec();
+
+        Ok(Some(tiles))
     }
 
     fn backtrack(
@@ -215,11 +336,13 @@ mod part_1 {
         combinations_by_id: &CombinationMap,
         outlines_by_id: &OutlineMap,
         outline_index: &OutlineIndex,
-    ) -> Option<Matrix<IdComb>> {
+    ) -> Result<Option<Matrix<IdComb>>> {
         if i >= solution.len() {
-            return Some(solution.to_vec());
+            return Ok(Some(solution.to_vec()));
         }
 
+        cancel::check()?;
+
         // compute next tile position
         let next = Some(j + 1)
             .filter(|&j| j < solution.len())
@@ -289,17 +412,17 @@ mod part_1 {
                     combinations_by_id,
                     outlines_by_id,
                     outline_index,
-                );
+                )?;
 
                 if let Some(solution) = complete_solution {
-                    return Some(solution);
+                    return Ok(Some(solution));
                 }
             }
             used.remove(&id);
         }
 
         // no valid solution found -> backtrack
-        None
+        Ok(None)
     }
 
     fn group_combinations_by_id(tiles: &[Tile]) -> CombinationMap {
@@ -385,13 +508,13 @@ mod part_2 {
         let size = tiles[0][0].pixels.len();
         let new_size = tiles.len() * (size - 2);
         let mut pixels = vec![vec![false; new_size]; new_size];
-        for ti in 0..tiles.len() {
-            for tj in 0..tiles[0].len() {
+        for (ti, row) in tiles.iter().enumerate() {
+            for (tj, tile) in row.iter().enumerate() {
                 for i in 1..(size - 1) {
                     for j in 1..(size - 1) {
                         let pi = ti * (size - 2) + i - 1;
                         let pj = tj * (size - 2) + j - 1;
-                        pixels[pi][pj] = tiles[ti][tj].pixels[i][j];
+                        pixels[pi][pj] = tile.pixels[i][j];
                     }
                 }
             }
@@ -400,17 +523,19 @@ mod part_2 {
         Tile { id: 0, pixels }
     }
 
-    pub(super) fn count_dragons(tile: &Tile) -> usize {
+    // top left corner of each dragon, as (row, column)
+    pub(super) fn find_dragons(tile: &Tile) -> Vec<(usize, usize)> {
         let size = tile.pixels[0].len();
 
         tile.pixels
             .windows(DRAGON.len())
-            .map(|candidate| {
+            .enumerate()
+            .flat_map(|(i, candidate)| {
                 (0..(size - DRAGON_LENGHT))
-                    .filter(|&offset| is_dragon(candidate, offset))
-                    .count()
+                    .filter(move |&offset| is_dragon(candidate, offset))
+                    .map(move |offset| (i, offset))
             })
-            .sum::<usize>()
+            .collect()
     }
 
     fn is_dragon(candidate: MatrixSlice<bool>, offset: usize) -> bool {
@@ -421,25 +546,9 @@ mod part_2 {
     }
 }
 
-/* tests */
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_first_challenge() {
-        let day = Day20::load(INPUT);
-        assert_eq!(day.first_challenge(), "20899048083289");
-    }
-
-    #[test]
-    fn test_second_challenge() {
-        let day = Day20::load(INPUT);
-        assert_eq!(day.second_challenge(), "273");
-    }
+/* examples */
 
-    const INPUT: &'static str = "Tile 2311:
+const INPUT: &str = "Tile 2311:
 ..##.#..#.
 ##..#.....
 #...##..#.
@@ -546,4 +655,54 @@ Tile 3079:
 ..#.###...
 ..#.......
 ..#.###...";
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "20899048083289"),
+    Example::second(INPUT, "273"),
+];
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_header() {
+        let error = Day20::load("Tile 2311\n..\n##").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 1:1: expected 'Tile <id>:'"
+        );
+
+        let error = Day20::load("Tile 1:\n#.\n.#").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 1:1: expected at least 3x3 tile, found 2x2"
+        );
+    }
+
+    #[test]
+    fn test_check_bad_rows() {
+        let tile = |id, broken| {
+            let mut rows = vec!["#.........".to_string(); 10];
+            if broken {
+                rows[2] = "#..x......".to_string();
+                rows[5] = "#....".to_string();
+            }
+            format!("Tile {}:\n{}", id, rows.join("\n"))
+        };
+
+        // only the broken rows, not the shape of what is left of the tile
+        for input in [tile(1, true), format!("{}\n\n{}", tile(1, true), tile(2, false))] {
+            let errors = parse::check(|| Day20::load(&input)).unwrap();
+            assert_eq!(
+                errors
+                    .iter()
+                    .map(|error| (error.line, error.column))
+                    .collect::<Vec<_>>(),
+                vec![(4, 4), (7, 6)]
+            );
+        }
+    }
 }
@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::{
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::{Error, Result};
+
+register_day!(21, "Allergen Assessment", Day21, EXAMPLES);
+
+pub struct Day21 {
+    input: Vec<Food>,
+}
+
+struct Food {
+    ingredients: HashSet<String>,
+    // not necessarily all of them
+    allergens: Vec<String>,
+}
+
+impl Day21 {
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: parse::lines(input, 1, Self::parse_food)?,
+        })
+    }
+
+    // "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)", allergens are optional
+    fn parse_food(line: &str) -> LineResult<Food> {
+        let (ingredients, allergens) = match line.split_once(" (contains ") {
+            Some((ingredients, allergens)) => {
+                let allergens = allergens
+                    .strip_suffix(')')
+                    .ok_or_else(|| LineError::new(line.len() + 1, "missing ')'"))?;
+                (ingredients, allergens.split(", ").collect())
+            }
+            None => (line, vec![]),
+        };
+
+        if ingredients.trim().is_empty() {
+            return Err(LineError::new(1, "missing ingredients"));
+        }
+
+        if let Some(&empty) = allergens.iter().find(|allergen| allergen.is_empty()) {
+            return Err(LineError::at(line, empty, "empty allergen"));
+        }
+
+        Ok(Food {
+            ingredients: ingredients.split_whitespace().map(String::from).collect(),
+            allergens: allergens.into_iter().map(String::from).collect(),
+        })
+    }
+
+    // an allergen can only be in the ingredients shared by every food that lists it
+    fn candidates(&self) -> HashMap<&str, HashSet<&str>> {
+        let mut candidates = HashMap::<&str, HashSet<&str>>::new();
+
+        for food in self.input.iter() {
+            let ingredients = food.ingredients.iter().map(String::as_str);
+            for allergen in food.allergens.iter() {
+                candidates
+                    .entry(allergen)
+                    .and_modify(|c| c.retain(|&i| food.ingredients.contains(i)))
+                    .or_insert_with(|| ingredients.clone().collect());
+            }
+        }
+
+        candidates
+    }
+
+    // the ingredient of each allergen, sorted by allergen
+    fn dangerous(&self) -> Result<BTreeMap<&str, &str>> {
+        let mut candidates = self.candidates();
+        let mut dangerous = BTreeMap::new();
+
+        // same old sudoku of day 16: pin down the sure ones and cross them out of the rest
+        while !candidates.is_empty() {
+            let (allergen, ingredient) = candidates
+                .iter()
+                .find(|(_, ingredients)| ingredients.len() == 1)
+                .map(|(&allergen, ingredients)| (allergen, *ingredients.iter().next().unwrap()))
+                .ok_or(Error::NotFound)?;
+
+            candidates.remove(allergen);
+            candidates.values_mut().for_each(|c| {
+                c.remove(ingredient);
+            });
+            dangerous.insert(allergen, ingredient);
+        }
+
+        Ok(dangerous)
+    }
+}
+
+impl Day for Day21 {
+    fn first_challenge(&self) -> Result<Answer> {
+        let suspects = self
+            .candidates()
+            .into_values()
+            .flatten()
+            .collect::<HashSet<_>>();
+
+        let safe = self
+            .input
+            .iter()
+            .flat_map(|food| food.ingredients.iter())
+            .filter(|ingredient| !suspects.contains(ingredient.as_str()))
+            .count();
+
+        Ok(safe.into())
+    }
+
+    fn second_challenge(&self) -> Result<Answer> {
+        let dangerous = self.dangerous()?;
+        let list = dangerous.values().copied().collect::<Vec<_>>().join(",");
+        Ok(list.into())
+    }
+}
+
+/* examples */
+
+const INPUT: &str = "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+trh fvjkl sbzzf mxmxvkd (contains dairy)
+sqjhc fvjkl (contains soy)
+sqjhc mxmxvkd sbzzf (contains fish)";
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "5"),
+    Example::second(INPUT, "mxmxvkd,sqjhc,fvjkl"),
+];
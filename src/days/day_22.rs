@@ -0,0 +1,170 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet, VecDeque},
+    hash::{Hash, Hasher},
+};
+
+use super::{
+    parse::{self, LineError},
+    Answer, Day, Example,
+};
+use crate::error::Result;
+
+register_day!(22, "Crab Combat", Day22, EXAMPLES);
+
+pub struct Day22 {
+    first: Deck,
+    second: Deck,
+}
+
+type Deck = VecDeque<usize>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Player {
+    First,
+    Second,
+}
+
+impl Day22 {
+    pub fn load(input: &str) -> Result<Self> {
+        let sections = parse::sections(input.trim_end());
+        let decks = parse::all(sections.iter().map(|&section| Self::parse_deck(section)))?;
+
+        match &decks[..] {
+            [first, second] => Ok(Self {
+                first: first.clone(),
+                second: second.clone(),
+            }),
+            _ => {
+                let line = input.lines().count() + 1;
+                let error = LineError::new(1, "expected the decks of two players");
+                Err(error.into_error(line).into())
+            }
+        }
+    }
+
+    // "Player 1:" and then a card per line
+    fn parse_deck((first_line, s): (usize, &str)) -> Result<Deck> {
+        let (header, cards) = s.split_once('\n').unwrap_or((s, ""));
+        if !(header.starts_with("Player ") && header.ends_with(':')) {
+            let error = LineError::new(1, "expected 'Player N:'");
+            return Err(error.into_error(first_line).into());
+        }
+
+        Ok(parse::lines(cards, first_line + 1, |line| parse::number(line, line))?.into())
+    }
+
+    fn combat(mut first: Deck, mut second: Deck) -> (Player, Deck) {
+        while !(first.is_empty() || second.is_empty()) {
+            let (a, b) = (first.pop_front().unwrap(), second.pop_front().unwrap());
+            if a > b {
+                first.extend([a, b]);
+            } else {
+                second.extend([b, a]);
+            }
+        }
+
+        Self::winner(first, second)
+    }
+
+    fn recursive_combat(mut first: Deck, mut second: Deck, top_level: bool) -> (Player, Deck) {
+        // only the winner of a sub-game matters: the highest card is larger than the number of
+        // cards in play, so it never starts a sub-game and whoever holds it never loses it.
+        // The first player cannot run out of cards then, and wins either way
+        if !top_level && first.iter().max() > second.iter().max() {
+            return (Player::First, first);
+        }
+
+        // hashes of the rounds already played, way cheaper than copies of the decks
+        let mut seen = HashSet::new();
+
+        while !(first.is_empty() || second.is_empty()) {
+            if !seen.insert(Self::state(&first, &second)) {
+                return (Player::First, first);
+            }
+
+            let (a, b) = (first.pop_front().unwrap(), second.pop_front().unwrap());
+            let winner = if a <= first.len() && b <= second.len() {
+                let first = first.iter().take(a).copied().collect();
+                let second = second.iter().take(b).copied().collect();
+                Self::recursive_combat(first, second, false).0
+            } else if a > b {
+                Player::First
+            } else {
+                Player::Second
+            };
+
+            match winner {
+                Player::First => first.extend([a, b]),
+                Player::Second => second.extend([b, a]),
+            }
+        }
+
+        Self::winner(first, second)
+    }
+
+    fn state(first: &Deck, second: &Deck) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (first, second).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn winner(first: Deck, second: Deck) -> (Player, Deck) {
+        if second.is_empty() {
+            (Player::First, first)
+        } else {
+            (Player::Second, second)
+        }
+    }
+
+    fn score(deck: &Deck) -> usize {
+        deck.iter()
+            .rev()
+            .enumerate()
+            .map(|(i, card)| (i + 1) * card)
+            .sum()
+    }
+}
+
+impl Day for Day22 {
+    fn first_challenge(&self) -> Result<Answer> {
+        let (_, deck) = Self::combat(self.first.clone(), self.second.clone());
+        Ok(Self::score(&deck).into())
+    }
+
+    fn second_challenge(&self) -> Result<Answer> {
+        let (_, deck) = Self::recursive_combat(self.first.clone(), self.second.clone(), true);
+        Ok(Self::score(&deck).into())
+    }
+}
+
+/* examples */
+
+const INPUT_1: &str = "Player 1:
+9
+2
+6
+3
+1
+
+Player 2:
+5
+8
+4
+7
+10";
+
+// would go on forever without the rule about repeated rounds
+const INPUT_2: &str = "Player 1:
+43
+19
+
+Player 2:
+2
+29
+14";
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT_1, "306"),
+    Example::second(INPUT_1, "291"),
+    Example::second(INPUT_2, "105"),
+];
@@ -0,0 +1,102 @@
+use super::{
+    parse::{self, LineError},
+    Answer, Day, Example,
+};
+use crate::{cancel, error::Result};
+
+register_day!(23, "Crab Cups", Day23, EXAMPLES);
+
+pub struct Day23 {
+    input: Vec<usize>,
+}
+
+impl Day23 {
+    pub fn load(input: &str) -> Result<Self> {
+        let line = input.trim_end();
+        let mut seen = vec![false; line.len() + 1];
+
+        // the labels go from 1 to the number of cups, each one once
+        let cups = line
+            .chars()
+            .enumerate()
+            .map(|(j, c)| match c.to_digit(10).map(|d| d as usize) {
+                Some(cup) if cup > 0 && cup <= line.len() && !seen[cup] => {
+                    seen[cup] = true;
+                    Ok(cup)
+                }
+                Some(_) => Err(LineError::new(j + 1, format!("unexpected cup '{}'", c))),
+                None => Err(LineError::new(j + 1, parse::invalid_char(c))),
+            })
+            .collect::<parse::LineResult<Vec<_>>>()
+            .map_err(|e| e.into_error(1))?;
+
+        // the current cup and the three picked up ones leave no room for a destination
+        if cups.len() < 5 {
+            let error = LineError::new(line.len() + 1, "expected at least 5 cups");
+            return Err(error.into_error(1).into());
+        }
+
+        Ok(Self { input: cups })
+    }
+
+    // The circle is a linked list in disguise: next[cup] is the cup clockwise of `cup`,
+    // so that moving three cups around is just a matter of fixing a few links.
+    // Cups after the labelled ones go on in order up to `size`
+    fn play(&self, size: usize, moves: usize) -> Result<Vec<usize>> {
+        let mut next = vec![0; size + 1];
+        let labels = self.input.iter().copied().chain(self.input.len() + 1..=size);
+        let mut cups = labels.clone().skip(1).chain(std::iter::once(self.input[0]));
+        for cup in labels {
+            next[cup] = cups.next().unwrap();
+        }
+
+        let mut current = self.input[0];
+        for i in 0..moves {
+            // millions of moves take a while
+            if i % 1_000_000 == 0 {
+                cancel::check()?;
+            }
+
+            let picked = [next[current], next[next[current]], next[next[next[current]]]];
+
+            let mut destination = current;
+            while destination == current || picked.contains(&destination) {
+                destination = if destination == 1 { size } else { destination - 1 };
+            }
+
+            next[current] = next[picked[2]];
+            next[picked[2]] = next[destination];
+            next[destination] = picked[0];
+            current = next[current];
+        }
+
+        Ok(next)
+    }
+}
+
+impl Day for Day23 {
+    fn first_challenge(&self) -> Result<Answer> {
+        let next = self.play(self.input.len(), 100)?;
+
+        let mut labels = String::new();
+        let mut cup = next[1];
+        while cup != 1 {
+            labels.push_str(&cup.to_string());
+            cup = next[cup];
+        }
+
+        Ok(labels.into())
+    }
+
+    fn second_challenge(&self) -> Result<Answer> {
+        let next = self.play(1_000_000, 10_000_000)?;
+        Ok((next[1] * next[next[1]]).into())
+    }
+}
+
+/* examples */
+
+const EXAMPLES: &[Example] = &[
+    Example::first("389125467", "67384529"),
+    Example::second("389125467", "149245887792").slow(),
+];
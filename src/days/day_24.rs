@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+
+use super::{
+    automaton,
+    parse::{self, LineError, LineResult},
+    Answer, Day, Example,
+};
+use crate::error::Result;
+
+register_day!(24, "Lobby Layout", Day24, EXAMPLES);
+
+pub struct Day24 {
+    input: Vec<Vec<Direction>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+// axial coordinates: east along q, south east along r
+type Tile = (i32, i32);
+
+impl Day24 {
+    const DIRECTIONS: [Direction; 6] = [
+        Direction::East,
+        Direction::SouthEast,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::NorthEast,
+    ];
+
+    pub fn load(input: &str) -> Result<Self> {
+        Ok(Self {
+            input: parse::lines(input, 1, Self::parse_path)?,
+        })
+    }
+
+    // "esenee" -> e, se, ne, e
+    fn parse_path(line: &str) -> LineResult<Vec<Direction>> {
+        let mut path = Vec::new();
+        let mut chars = line.char_indices();
+
+        while let Some((j, c)) = chars.next() {
+            let direction = match c {
+                'e' => Direction::East,
+                'w' => Direction::West,
+                'n' | 's' => match (c, chars.next()) {
+                    ('s', Some((_, 'e'))) => Direction::SouthEast,
+                    ('s', Some((_, 'w'))) => Direction::SouthWest,
+                    ('n', Some((_, 'w'))) => Direction::NorthWest,
+                    ('n', Some((_, 'e'))) => Direction::NorthEast,
+                    (_, Some((j, c))) => return Err(LineError::new(j + 1, parse::invalid_char(c))),
+                    (_, None) => return Err(LineError::new(j + 2, "expected 'e' or 'w'")),
+                },
+                _ => return Err(LineError::new(j + 1, parse::invalid_char(c))),
+            };
+            path.push(direction);
+        }
+
+        Ok(path)
+    }
+
+    fn step((q, r): Tile, direction: Direction) -> Tile {
+        match direction {
+            Direction::East => (q + 1, r),
+            Direction::SouthEast => (q, r + 1),
+            Direction::SouthWest => (q - 1, r + 1),
+            Direction::West => (q - 1, r),
+            Direction::NorthWest => (q, r - 1),
+            Direction::NorthEast => (q + 1, r - 1),
+        }
+    }
+
+    fn neighbours(tile: Tile) -> impl Iterator<Item = Tile> {
+        Self::DIRECTIONS
+            .iter()
+            .map(move |&direction| Self::step(tile, direction))
+    }
+
+    // every path flips the tile where it ends
+    fn black_tiles(&self) -> HashSet<Tile> {
+        let mut black = HashSet::new();
+        for path in self.input.iter() {
+            let tile = path.iter().fold((0, 0), |tile, &d| Self::step(tile, d));
+            if !black.remove(&tile) {
+                black.insert(tile);
+            }
+        }
+        black
+    }
+
+    fn stays_black(black: bool, neighbours: usize) -> bool {
+        match (black, neighbours) {
+            (true, 1) | (true, 2) => true,
+            (true, _) => false,
+
+            (false, 2) => true,
+            (false, _) => false,
+        }
+    }
+}
+
+impl Day for Day24 {
+    fn first_challenge(&self) -> Result<Answer> {
+        Ok(self.black_tiles().len().into())
+    }
+
+    fn second_challenge(&self) -> Result<Answer> {
+        let mut black = self.black_tiles();
+        for _ in 0..100 {
+            black = automaton::step(&black, Self::neighbours, Self::stays_black)?;
+        }
+
+        Ok(black.len().into())
+    }
+}
+
+/* examples */
+
+const INPUT: &str = "sesenwnenenewseeswwswswwnenewsewsw
+neeenesenwnwwswnenewnwwsewnenwseswesw
+seswneswswsenwwnwse
+nwnwneseeswswnenewneswwnewseswneseene
+swweswneswnenwsewnwneneseenw
+eesenwseswswnenwswnwnwsewwnwsene
+sewnenenenesenwsewnenwwwse
+wenwwweseeeweswwwnwwe
+wsweesenenewnwwnwsenewsenwwsesesenwne
+neeswseenwwswnwswswnw
+nenwswwsewswnenenewsenwsenwnesesenew
+enewnwewneswsewnwswenweswnenwsenwsw
+sweneswneswneneenwnewenewwneswswnese
+swwesenesewenwneswnwwneseswwne
+enesenwswwswneneswsenwnewswseenwsese
+wnwnesenesenenwwnenwsewesewsesesew
+nenewswnwewswnenesenwnesewesw
+eneswnwswnwsenenwnwnwwseeswneewsenese
+neswnwewnwnwseenwseesewsenwsweewe
+wseweeenwnesenwwwswnew";
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "10"),
+    Example::second(INPUT, "2208"),
+];
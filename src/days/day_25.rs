@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use super::{
+    parse::{self, LineError},
+    Answer, Day, Example,
+};
+use crate::error::{Error, Result};
+
+register_day!(25, "Combo Breaker", Day25, EXAMPLES);
+
+pub struct Day25 {
+    card: u64,
+    door: u64,
+}
+
+impl Day25 {
+    const SUBJECT: u64 = 7;
+    const MODULUS: u64 = 20201227;
+
+    pub fn load(input: &str) -> Result<Self> {
+        let keys = parse::lines(input, 1, |line| {
+            let key = parse::number::<u64>(line, line)?;
+            if key >= Self::MODULUS {
+                return Err(LineError::new(1, format!("expected a key below {}", Self::MODULUS)));
+            }
+            Ok(key)
+        })?;
+
+        match keys[..] {
+            [card, door] => Ok(Self { card, door }),
+            _ => {
+                let line = input.lines().count() + 1;
+                let error = LineError::new(1, "expected the keys of the card and the door");
+                Err(error.into_error(line).into())
+            }
+        }
+    }
+
+    fn pow_mod(base: u64, mut exp: u64) -> u64 {
+        let mut base = base % Self::MODULUS;
+        let mut result = 1;
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = result * base % Self::MODULUS;
+            }
+            base = base * base % Self::MODULUS;
+            exp /= 2;
+        }
+
+        result
+    }
+
+    // Baby-step giant-step: the loop size is i * m + j for some i, j < m = sqrt(modulus),
+    // so remember the m baby steps 7^j and take giant steps of 7^-m from the key until
+    // one of them shows up. A few thousand steps instead of millions of loops
+    fn loop_size(key: u64) -> Option<u64> {
+        let m = (Self::MODULUS as f64).sqrt().ceil() as u64;
+
+        let mut baby_steps = HashMap::new();
+        let mut value = 1;
+        for j in 0..m {
+            baby_steps.entry(value).or_insert(j);
+            value = value * Self::SUBJECT % Self::MODULUS;
+        }
+
+        // the modulus is prime, so 7^-m = 7^(modulus - 1 - m)
+        let giant_step = Self::pow_mod(Self::SUBJECT, Self::MODULUS - 1 - m);
+        let mut value = key;
+        for i in 0..m {
+            if let Some(j) = baby_steps.get(&value) {
+                return Some(i * m + j);
+            }
+            value = value * giant_step % Self::MODULUS;
+        }
+
+        None
+    }
+}
+
+impl Day for Day25 {
+    fn first_challenge(&self) -> Result<Answer> {
+        let loop_size = Self::loop_size(self.card).ok_or(Error::NotFound)?;
+        Ok(Self::pow_mod(self.door, loop_size).into())
+    }
+
+    // the last star comes for free with the other 49
+    fn second_challenge(&self) -> Result<Answer> {
+        Ok(Answer::None)
+    }
+}
+
+/* examples */
+
+const INPUT: &str = "5764801
+17807724";
+
+const EXAMPLES: &[Example] = &[
+    Example::first(INPUT, "14897079"),
+    Example::second(INPUT, "none"),
+];
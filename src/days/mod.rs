@@ -1,73 +1,368 @@
-use std::{fs::read_to_string, ops::RangeInclusive, path::Path};
-
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_17;
-mod day_18;
-mod day_19;
-mod day_20;
-
-pub trait Day {
-    fn first_challenge(&self) -> String;
-    fn second_challenge(&self) -> String;
+use std::{
+    any::Any,
+    collections::HashMap,
+    env,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+pub use crate::answer::Answer;
+use crate::{
+    error::{Error, ParseError, Result},
+    frame::Frame,
+};
+
+// declares the registry entry of a day module, e.g. `register_day!(1, "Report Repair", Day01);`
+// the puzzle examples, the alternative implementations and the queries, if any, go last
+macro_rules! register_day {
+    ($number:expr, $title:expr, $day:ident) => {
+        register_day!($number, $title, $day, &[]);
+    };
+    ($number:expr, $title:expr, $day:ident, $examples:expr) => {
+        register_day!($number, $title, $day, $examples, &[]);
+    };
+    ($number:expr, $title:expr, $day:ident, $examples:expr, $implementations:expr) => {
+        register_day!($number, $title, $day, $examples, $implementations, &[]);
+    };
+    (
+        $number:expr, $title:expr, $day:ident,
+        $examples:expr, $implementations:expr, $queries:expr
+    ) => {
+        pub const ENTRY: super::DayEntry = super::DayEntry {
+            number: $number,
+            title: $title,
+            load: |input| Ok(Box::new($day::load(input)?)),
+            examples: $examples,
+            implementations: $implementations,
+            queries: $queries,
+        };
+    };
 }
 
+// adding a day is just a matter of listing its module here, numbering may have gaps
+macro_rules! days {
+    ($($module:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        const REGISTRY: &[DayEntry] = &[$($module::ENTRY),*];
+    };
+}
+
+days!(
+    day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, day_10, day_11, day_12,
+    day_13, day_14, day_15, day_16, day_17, day_18, day_19, day_20, day_21, day_22, day_23, day_24,
+    day_25,
+);
+
+lazy_static! {
+    // sorted numbers of the registered days
+    pub static ref DAY_NUMBERS: Vec<usize> = {
+        let mut numbers = REGISTRY.iter().map(|entry| entry.number).collect::<Vec<_>>();
+        numbers.sort_unstable();
+        numbers
+    };
+}
+
+pub struct DayEntry {
+    pub number: usize,
+    pub title: &'static str,
+    pub load: fn(&str) -> Result<Box<dyn Day>>,
+    pub examples: &'static [Example],
+    // besides the challenges of the `Day` trait, which are the default ones
+    pub implementations: &'static [Implementation],
+    pub queries: &'static [Query],
+}
+
+impl DayEntry {
+    pub fn solve(&self, part: Part, input: &str) -> Result<Answer> {
+        part.solve((self.load)(input)?.as_ref())
+    }
+
+    // every problem of the input instead of just the first one, see `parse::check`
+    pub fn check(&self, input: &str) -> Result<Vec<ParseError>> {
+        parse::check(|| (self.load)(input))
+    }
+}
+
+pub fn entry(number: usize) -> Option<&'static DayEntry> {
+    REGISTRY.iter().find(|entry| entry.number == number)
+}
+
+// an example input of the puzzle text along with its answer
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    pub part: Part,
+    pub expected: &'static str,
+    // too slow for a debug build, tests skip it unless asked to
+    pub slow: bool,
+}
+
+impl Example {
+    pub const fn first(input: &'static str, expected: &'static str) -> Self {
+        Self::new(input, Part::First, expected)
+    }
+
+    pub const fn second(input: &'static str, expected: &'static str) -> Self {
+        Self::new(input, Part::Second, expected)
+    }
+
+    const fn new(input: &'static str, part: Part, expected: &'static str) -> Self {
+        Self {
+            input,
+            part,
+            expected,
+            slow: false,
+        }
+    }
+
+    pub const fn slow(self) -> Self {
+        Self { slow: true, ..self }
+    }
+}
+
+// another way to solve a part of a day, e.g. a faster or a simpler algorithm,
+// which must get the same answer of the default one
+#[derive(Clone, Copy)]
+pub struct Implementation {
+    pub name: &'static str,
+    pub part: Part,
+    // gets the day of the registry entry, see `downcast`
+    pub solve: fn(&dyn Day) -> Result<Answer>,
+}
+
+impl Implementation {
+    // the name of the challenges of the `Day` trait
+    pub const DEFAULT: &'static str = "default";
+
+    pub const fn first(name: &'static str, solve: fn(&dyn Day) -> Result<Answer>) -> Self {
+        Self {
+            name,
+            part: Part::First,
+            solve,
+        }
+    }
+
+    pub const fn second(name: &'static str, solve: fn(&dyn Day) -> Result<Answer>) -> Self {
+        Self {
+            name,
+            part: Part::Second,
+            solve,
+        }
+    }
+}
+
+// a question about a loaded day other than its challenges, e.g. for the repl
+#[derive(Clone, Copy)]
+pub struct Query {
+    pub name: &'static str,
+    // what goes after the name, e.g. "<color>"
+    pub args: &'static str,
+    // gets the day of the registry entry, see `downcast`, and the rest of the line
+    pub run: fn(&dyn Day, &str) -> Result<Answer>,
+}
+
+impl Query {
+    pub const fn new(
+        name: &'static str,
+        args: &'static str,
+        run: fn(&dyn Day, &str) -> Result<Answer>,
+    ) -> Self {
+        Self { name, args, run }
+    }
+}
+
+// implementations and queries are only given the day they are registered with
+pub fn downcast<T: Day>(day: &dyn Day) -> &T {
+    let day: &dyn Any = day;
+    day.downcast_ref()
+        .expect("aaaah! implementation registered with the wrong day")
+}
+
+pub mod automaton;
+pub mod parse;
+
+pub trait Day: Any {
+    fn first_challenge(&self) -> Result<Answer>;
+    fn second_challenge(&self) -> Result<Answer>;
+
+    // how the challenge gets solved, frame by frame... if there is anything worth seeing
+    fn visualize(&self, _part: Part) -> Result<Vec<Frame>> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Part {
+    First,
+    Second,
+}
+
+impl Part {
+    pub fn solve(self, day: &dyn Day) -> Result<Answer> {
+        match self {
+            Part::First => day.first_challenge(),
+            Part::Second => day.second_challenge(),
+        }
+    }
+}
+
+// inputs are read from `<input_folder>/day_NN` unless the day has its own input
+#[derive(Clone)]
 pub struct Advent {
     input_folder: String,
+    inputs: HashMap<usize, Input>,
+}
+
+#[derive(Clone)]
+enum Input {
+    File(String),
+    // e.g. piped from stdin
+    Text(String),
 }
 
 impl Advent {
-    pub const DAY_NUMBERS: RangeInclusive<usize> = 1..=20;
+    // where to look for the input folder when it is not given explicitly
+    pub const INPUT_DIR_VAR: &'static str = "AOC_INPUT_DIR";
 
     pub fn new(input_folder: String) -> Self {
-        Self { input_folder }
-    }
-
-    pub fn load_day(&self, number: usize) -> Box<dyn Day> {
-        let input_path = &Path::new(&self.input_folder)
-            .join(format!("day_{:02}", number))
-            .to_str()
-            .unwrap()
-            .to_owned();
-
-        let input = &read_to_string(input_path).expect("Load input failed");
-
-        match number {
-            1 => Box::new(day_01::Day01::load(input)),
-            2 => Box::new(day_02::Day02::load(input)),
-            3 => Box::new(day_03::Day03::load(input)),
-            4 => Box::new(day_04::Day04::load(input)),
-            5 => Box::new(day_05::Day05::load(input)),
-            6 => Box::new(day_06::Day06::load(input)),
-            7 => Box::new(day_07::Day07::load(input)),
-            8 => Box::new(day_08::Day08::load(input)),
-            9 => Box::new(day_09::Day09::load(input)),
-            10 => Box::new(day_10::Day10::load(input)),
-            11 => Box::new(day_11::Day11::load(input)),
-            12 => Box::new(day_12::Day12::load(input)),
-            13 => Box::new(day_13::Day13::load(input)),
-            14 => Box::new(day_14::Day14::load(input)),
-            15 => Box::new(day_15::Day15::load(input)),
-            16 => Box::new(day_16::Day16::load(input)),
-            17 => Box::new(day_17::Day17::load(input)),
-            18 => Box::new(day_18::Day18::load(input)),
-            19 => Box::new(day_19::Day19::load(input)),
-            20 => Box::new(day_20::Day20::load(input)),
-            _ => panic!("Error 404: day {} not found!", number),
+        Self {
+            input_folder,
+            inputs: HashMap::new(),
+        }
+    }
+
+    // None when the variable is not set
+    pub fn from_env() -> Option<Self> {
+        env::var(Self::INPUT_DIR_VAR).ok().map(Self::new)
+    }
+
+    // read the input of the given day from `path` instead of the input folder
+    pub fn with_input_file(mut self, number: usize, path: String) -> Self {
+        self.inputs.insert(number, Input::File(path));
+        self
+    }
+
+    // use `input` as it is instead of reading any file
+    pub fn with_input(mut self, number: usize, input: String) -> Self {
+        self.inputs.insert(number, Input::Text(input));
+        self
+    }
+
+    // None when the input of the day is not read from a file
+    pub fn input_path(&self, number: usize) -> Option<PathBuf> {
+        match self.inputs.get(&number) {
+            Some(Input::Text(_)) => None,
+            Some(Input::File(path)) => Some(Path::new(path).to_path_buf()),
+            None => Some(Path::new(&self.input_folder).join(format!("day_{:02}", number))),
+        }
+    }
+
+    pub fn read_input(&self, number: usize) -> Result<String> {
+        let input_path = match (self.inputs.get(&number), self.input_path(number)) {
+            (Some(Input::Text(input)), _) => return Ok(input.clone()),
+            (_, Some(path)) => path,
+            (_, None) => unreachable!("aaaah! only text inputs have no path"),
+        };
+
+        read_to_string(&input_path).map_err(|e| Error::Io {
+            path: input_path.to_string_lossy().into_owned(),
+            kind: e.kind(),
+        })
+    }
+
+    pub fn load_day(&self, number: usize) -> Result<Box<dyn Day>> {
+        let entry = entry(number).ok_or(Error::UnknownDay(number))?;
+        (entry.load)(&self.read_input(number)?)
+    }
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let numbers = REGISTRY
+            .iter()
+            .map(|entry| entry.number)
+            .collect::<HashSet<_>>();
+        assert_eq!(numbers.len(), REGISTRY.len(), "duplicate day number");
+        assert_eq!(DAY_NUMBERS.len(), REGISTRY.len());
+        assert!(DAY_NUMBERS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(REGISTRY.iter().all(|entry| !entry.title.is_empty()));
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        let advent = Advent::new("src/input".to_string());
+        assert_eq!(advent.load_day(0).err(), Some(Error::UnknownDay(0)));
+        assert!(advent.load_day(DAY_NUMBERS[0]).is_ok());
+    }
+
+    #[test]
+    fn test_inputs() {
+        let advent = Advent::new("nowhere".to_string())
+            .with_input(1, "1721\n979\n366\n299\n675\n1456".to_string())
+            .with_input_file(2, "src/input/day_02".to_string());
+
+        let day = advent.load_day(1).unwrap();
+        assert_eq!(day.first_challenge().unwrap(), "514579");
+        assert!(advent.load_day(2).is_ok());
+
+        assert_eq!(advent.input_path(1), None);
+        assert_eq!(
+            advent.input_path(2),
+            Some(Path::new("src/input/day_02").to_path_buf())
+        );
+
+        let error = advent.load_day(3).err().unwrap();
+        assert!(matches!(error, Error::Io { path, .. } if path == "nowhere/day_03"));
+    }
+
+    #[test]
+    fn test_check() {
+        let errors = entry(1).unwrap().check("1721\nx\n366\n-\n").unwrap();
+        let positions = errors
+            .iter()
+            .map(|error| (error.line, error.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 1), (4, 1)]);
+
+        let advent = Advent::new("src/input".to_string());
+        for &number in DAY_NUMBERS.iter() {
+            let input = advent.read_input(number).unwrap();
+            assert_eq!(entry(number).unwrap().check(&input), Ok(vec![]));
+        }
+    }
+
+    // whatever the input, checking it must not panic
+    #[test]
+    fn test_check_garbage() {
+        let garbage = ["", "x", "\n\n", "1\n\n\n2", "#.#\n\n: -", "a,b\n1-2"];
+        for &number in DAY_NUMBERS.iter() {
+            for input in garbage.iter() {
+                assert!(entry(number).unwrap().check(input).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_visualize() {
+        for &number in [11, 12, 17, 20].iter() {
+            let entry = entry(number).unwrap();
+            for &part in [Part::First, Part::Second].iter() {
+                let example = entry.examples.iter().find(|e| e.part == part).unwrap();
+                let day = (entry.load)(example.input).unwrap();
+                assert!(!day.visualize(part).unwrap().is_empty(), "day {}", number);
+            }
         }
+
+        let day = (entry(1).unwrap().load)(entry(1).unwrap().examples[0].input).unwrap();
+        assert_eq!(day.visualize(Part::First), Ok(vec![]));
     }
 }
@@ -0,0 +1,266 @@
+use std::{cell::RefCell, fmt::Display, str::FromStr};
+
+use crate::error::{Error, ParseError, Result};
+
+// While checking an input the parsers don't stop at the first problem: each error is
+// recorded and the offending line or section skipped, so that a single pass finds them all
+thread_local! {
+    static DIAGNOSTICS: RefCell<Option<Vec<ParseError>>> = const { RefCell::new(None) };
+}
+
+pub type LineResult<T> = std::result::Result<T, LineError>;
+
+// Parse error inside a single line: the line number is attached by whoever iterates over lines
+#[derive(Debug)]
+pub struct LineError {
+    column: usize,
+    message: String,
+}
+
+impl LineError {
+    pub fn new(column: usize, message: impl Into<String>) -> Self {
+        Self {
+            column,
+            message: message.into(),
+        }
+    }
+
+    // `token` must be a slice of `line`, so that we can figure out where it starts
+    pub fn at(line: &str, token: &str, message: impl Into<String>) -> Self {
+        let column = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize) + 1;
+        Self::new(column, message)
+    }
+
+    pub fn into_error(self, line: usize) -> ParseError {
+        ParseError {
+            line,
+            column: self.column,
+            message: self.message,
+        }
+    }
+}
+
+// Parse each line, numbering them starting from `first_line`
+pub fn lines<T, F>(s: &str, first_line: usize, mut parse: F) -> Result<Vec<T>>
+where
+    F: FnMut(&str) -> LineResult<T>,
+{
+    all(s
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|e| e.into_error(first_line + i).into())))
+}
+
+// Like collecting into a Result, but parse errors are skipped while checking
+pub fn all<T, I>(results: I) -> Result<Vec<T>>
+where
+    I: IntoIterator<Item = Result<T>>,
+{
+    let mut values = Vec::new();
+
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(Error::Parse(error)) if record(&error) => (),
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(values)
+}
+
+// false when not checking
+fn record(error: &ParseError) -> bool {
+    DIAGNOSTICS.with(|diagnostics| match diagnostics.borrow_mut().as_mut() {
+        Some(errors) => {
+            errors.push(error.clone());
+            true
+        }
+        None => false,
+    })
+}
+
+// Every parse error found by `load`, sorted by position.
+// Other errors, e.g. a missing input, are returned as they are
+pub fn check<T, F>(load: F) -> Result<Vec<ParseError>>
+where
+    F: FnOnce() -> Result<T>,
+{
+    DIAGNOSTICS.with(|diagnostics| *diagnostics.borrow_mut() = Some(Vec::new()));
+    let result = load();
+    let mut errors = DIAGNOSTICS
+        .with(|diagnostics| diagnostics.borrow_mut().take())
+        .unwrap_or_default();
+
+    // whatever stopped the load for good comes last
+    match result {
+        Ok(_) => (),
+        Err(Error::Parse(error)) => errors.push(error),
+        Err(error) => return Err(error),
+    }
+
+    errors.sort_by_key(|error| (error.line, error.column));
+    errors.dedup();
+    Ok(errors)
+}
+
+// Parse a rectangular grid of characters
+pub fn grid<T, F>(s: &str, first_line: usize, mut parse: F) -> Result<Vec<Vec<T>>>
+where
+    F: FnMut(char) -> Option<T>,
+{
+    let mut width = None;
+    let mut skipped = None;
+
+    let grid = all(s.lines().enumerate().map(|(i, line)| {
+        let row = line
+            .char_indices()
+            .map(|(j, c)| parse(c).ok_or_else(|| LineError::new(j + 1, invalid_char(c))))
+            .collect::<LineResult<Vec<_>>>();
+
+        let row = row.and_then(|row| match *width.get_or_insert(row.len()) {
+            n if n == row.len() => Ok(row),
+            n => Err(LineError::new(
+                n.min(row.len()) + 1,
+                format!("expected {} columns, found {}", n, row.len()),
+            )),
+        });
+
+        row.map_err(|e| {
+            let error = e.into_error(first_line + i);
+            skipped.get_or_insert_with(|| error.clone());
+            error.into()
+        })
+    }))?;
+
+    // While checking, a grid with skipped rows has the wrong shape for any further check:
+    // its first error is returned again, the same one is reported only once anyway
+    if let Some(error) = skipped {
+        return Err(error.into());
+    }
+
+    if grid.is_empty() || grid[0].is_empty() {
+        return Err(LineError::new(1, "empty grid")
+            .into_error(first_line)
+            .into());
+    }
+
+    Ok(grid)
+}
+
+// Split on blank lines, pairing each section with the number of its first line
+pub fn sections(s: &str) -> Vec<(usize, &str)> {
+    let mut first_line = 1;
+    s.split("\n\n")
+        .map(|section| {
+            let numbered = (first_line, section);
+            first_line += section.matches('\n').count() + 2;
+            numbered
+        })
+        .collect()
+}
+
+// `token` must be a slice of `line`
+pub fn number<T>(line: &str, token: &str) -> LineResult<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    token
+        .parse::<T>()
+        .map_err(|e| LineError::at(line, token, format!("invalid number '{}': {}", token, e)))
+}
+
+// `token` must be a slice of `line`
+pub fn split_pair<'a>(
+    line: &str,
+    token: &'a str,
+    separator: &str,
+) -> LineResult<(&'a str, &'a str)> {
+    token
+        .split_once(separator)
+        .ok_or_else(|| LineError::at(line, token, format!("missing '{}'", separator)))
+}
+
+pub fn invalid_char(c: char) -> String {
+    format!("invalid character '{}'", c)
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let error = lines("1\n2\nx3", 1, |line| number::<u32>(line, line)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 3:1: invalid number 'x3': invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let parse = |s| lines(s, 1, |line| number::<u32>(line, line));
+        let errors = check(|| parse("1\nx\n3\ny")).unwrap();
+        let positions = errors
+            .iter()
+            .map(|error| (error.line, error.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 1), (4, 1)]);
+        assert_eq!(check(|| parse("1\n2")), Ok(vec![]));
+
+        // back to the first error only
+        assert!(parse("1\nx\n3\ny").is_err());
+
+        let missing = check(|| -> Result<()> { Err(Error::NotFound) });
+        assert_eq!(missing, Err(Error::NotFound));
+    }
+
+    #[test]
+    fn test_grid() {
+        let parse = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        assert_eq!(
+            grid("#.\n.#", 1, parse).unwrap(),
+            vec![vec![true, false], vec![false, true]]
+        );
+        assert_eq!(
+            grid("#.\n.?", 1, parse).unwrap_err().to_string(),
+            "parse error at 2:2: invalid character '?'"
+        );
+        assert_eq!(
+            grid("#.\n.", 1, parse).unwrap_err().to_string(),
+            "parse error at 2:2: expected 2 columns, found 1"
+        );
+        assert_eq!(
+            grid("", 1, parse).unwrap_err().to_string(),
+            "parse error at 1:1: empty grid"
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "a\nb\n\nc\n\nd\ne\nf";
+        let first_lines = sections(input)
+            .iter()
+            .map(|&(line, _)| line)
+            .collect::<Vec<_>>();
+        assert_eq!(first_lines, vec![1, 4, 6]);
+    }
+
+    #[test]
+    fn test_split_pair() {
+        let line = "mem[8] = 11";
+        assert_eq!(split_pair(line, line, " = ").unwrap(), ("mem[8]", "11"));
+        let error = split_pair(line, &line[7..], ": ")
+            .unwrap_err()
+            .into_error(1);
+        assert_eq!(error.column, 8);
+    }
+}
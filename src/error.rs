@@ -0,0 +1,54 @@
+use std::{fmt, io};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Io { path: String, kind: io::ErrorKind },
+    Write { path: String, kind: io::ErrorKind },
+    Parse(ParseError),
+    UnknownDay(usize),
+    NotFound,
+    Timeout,
+    Panicked,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, kind } => write!(f, "cannot read {}: {}", path, kind),
+            Error::Write { path, kind } => write!(f, "cannot write {}: {}", path, kind),
+            Error::Parse(error) => error.fmt(f),
+            Error::UnknownDay(number) => write!(f, "404 day {} not found", number),
+            Error::NotFound => write!(f, "no solution found"),
+            Error::Timeout => write!(f, "TIMEOUT"),
+            Error::Panicked => write!(f, "the day panicked"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+// line and column are 1-based, like any decent text editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "parse error at {}:{}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
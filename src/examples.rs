@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    days::{self, Answer, Example, Part},
+    error::Result,
+};
+
+// `index` counts the examples of the same day from 1, whatever part was selected
+pub struct ExampleResult {
+    pub number: usize,
+    pub index: usize,
+    pub example: Example,
+    pub result: Result<Answer>,
+    pub elapsed: Duration,
+}
+
+impl ExampleResult {
+    pub fn is_correct(&self) -> bool {
+        matches!(&self.result, Ok(answer) if answer.matches(self.example.expected))
+    }
+}
+
+// unknown days have no examples, both parts are run when `part` is not set
+pub fn run_examples(day_numbers: &[usize], part: Option<Part>) -> Vec<ExampleResult> {
+    let entries = day_numbers.iter().filter_map(|&number| days::entry(number));
+
+    entries
+        .flat_map(|entry| {
+            entry
+                .examples
+                .iter()
+                .enumerate()
+                .filter(move |(_, example)| part.map(|part| part == example.part).unwrap_or(true))
+                .map(move |(i, example)| {
+                    let clock = Instant::now();
+                    let result = entry.solve(example.part, example.input);
+
+                    ExampleResult {
+                        number: entry.number,
+                        index: i + 1,
+                        example: *example,
+                        result,
+                        elapsed: clock.elapsed(),
+                    }
+                })
+        })
+        .collect()
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::DAY_NUMBERS;
+
+    fn check_examples(slow: bool) {
+        for &number in DAY_NUMBERS.iter() {
+            let entry = days::entry(number).unwrap();
+            for (i, example) in entry.examples.iter().enumerate() {
+                if example.slow != slow {
+                    continue;
+                }
+
+                let answer = entry.solve(example.part, example.input);
+                assert_eq!(
+                    answer.map(|answer| answer.to_string()),
+                    Ok(example.expected.to_string()),
+                    "day {} example {}",
+                    number,
+                    i + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        check_examples(false);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_slow_examples() {
+        check_examples(true);
+    }
+
+    #[test]
+    fn test_run_examples() {
+        let results = run_examples(&[10, 42, 1], Some(Part::Second));
+        let examples = results
+            .iter()
+            .map(|result| (result.number, result.index))
+            .collect::<Vec<_>>();
+        assert_eq!(examples, vec![(10, 2), (10, 3), (1, 2)]);
+        assert!(results.iter().all(ExampleResult::is_correct));
+    }
+}
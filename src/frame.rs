@@ -0,0 +1,212 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Error, Result};
+
+// A single picture of a visualization: plain text, or an image saved as a binary PPM
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Text(Vec<String>),
+    Image(Image),
+}
+
+pub type Color = [u8; 3];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    // each cell of the grid becomes a square of `scale` pixels
+    pub fn from_grid<T, F>(grid: &[Vec<T>], scale: usize, color: F) -> Self
+    where
+        F: Fn(&T) -> Color,
+    {
+        let width = grid.first().map_or(0, |row| row.len());
+        let mut image = Self::new(width * scale, grid.len() * scale, [0, 0, 0]);
+
+        for (i, row) in grid.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                image.fill(j * scale, i * scale, scale, color(cell));
+            }
+        }
+
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Color> {
+        Some(self.pixels[self.index(x, y)?])
+    }
+
+    // pixels out of the image are just not drawn
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = color;
+        }
+    }
+
+    // square of `size` pixels with its top left corner in (x, y)
+    pub fn fill(&mut self, x: usize, y: usize, size: usize, color: Color) {
+        for yy in y..y + size {
+            for xx in x..x + size {
+                self.set(xx, yy, color);
+            }
+        }
+    }
+
+    // Bresenham, good old friend
+    pub fn line(&mut self, (x0, y0): (usize, usize), (x1, y1): (usize, usize), color: Color) {
+        let (mut x, mut y) = (x0 as isize, y0 as isize);
+        let (x1, y1) = (x1 as isize, y1 as isize);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut error = dx + dy;
+
+        loop {
+            self.set(x as usize, y as usize, color);
+            if x == x1 && y == y1 {
+                break;
+            }
+
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        Some(y * self.width + x).filter(|_| x < self.width && y < self.height)
+    }
+
+    fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.pixels.iter().flatten());
+        bytes
+    }
+}
+
+impl Frame {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Frame::Text(_) => "txt",
+            Frame::Image(_) => "ppm",
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Frame::Text(lines) => lines
+                .iter()
+                .map(|line| format!("{}\n", line))
+                .collect::<String>()
+                .into_bytes(),
+            Frame::Image(image) => image.to_ppm(),
+        }
+    }
+}
+
+// Writes the frames in `dir`, created if missing, as frame_0001.txt, frame_0002.ppm and so on
+pub fn render(frames: &[Frame], dir: &Path) -> Result<Vec<PathBuf>> {
+    let io_error = |path: &Path, e: std::io::Error| Error::Write {
+        path: path.to_string_lossy().into_owned(),
+        kind: e.kind(),
+    };
+
+    fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+
+    frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let path = dir.join(format!("frame_{:04}.{}", i + 1, frame.extension()));
+            fs::write(&path, frame.to_bytes()).map_err(|e| io_error(&path, e))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_image() {
+        let grid = vec![vec![true, false], vec![false, true]];
+        let mut image = Image::from_grid(&grid, 2, |&on| if on { [255; 3] } else { [0; 3] });
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.get(1, 1), Some([255; 3]));
+        assert_eq!(image.get(2, 1), Some([0; 3]));
+        assert_eq!(image.get(4, 0), None);
+
+        image.line((0, 3), (3, 0), [1, 2, 3]);
+        assert!((0..4).all(|i| image.get(i, 3 - i) == Some([1, 2, 3])));
+        image.set(10, 10, [1, 2, 3]);
+
+        let mut flat = Image::new(5, 2, [0; 3]);
+        flat.line((4, 1), (0, 0), [9; 3]);
+        let drawn = (0..5).flat_map(|x| (0..2).map(move |y| (x, y)));
+        assert_eq!(
+            drawn
+                .filter(|&(x, y)| flat.get(x, y) == Some([9; 3]))
+                .count(),
+            5
+        );
+
+        let ppm = Frame::Image(image).to_bytes();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+    }
+
+    #[test]
+    fn test_render() {
+        let dir = env::temp_dir().join(format!("aoc2020_frames_{}", std::process::id()));
+        let frames = vec![
+            Frame::Text(vec!["#.".to_string(), ".#".to_string()]),
+            Frame::Image(Image::new(1, 1, [0; 3])),
+        ];
+
+        let paths = render(&frames, &dir).unwrap();
+        assert_eq!(
+            paths,
+            vec![dir.join("frame_0001.txt"), dir.join("frame_0002.ppm")]
+        );
+        assert_eq!(fs::read_to_string(&paths[0]).unwrap(), "#.\n.#\n");
+
+        // a file is in the way of the directory
+        let error = render(&frames, &paths[0]).unwrap_err();
+        assert!(error.to_string().starts_with("cannot write "));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,408 @@
+use std::{
+    fs::{read_to_string, OpenOptions},
+    io::{ErrorKind, Write},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    days::parse::{self, LineError, LineResult},
+    error::{Error, Result},
+    report::{DayResult, Report},
+    stats::Stats,
+};
+
+// Append only store of the timings of every run, one line per day:
+// "<run> <unix time> <commit> <tag> <day> <load ns> <first ns> <second ns>"
+// where a missing tag or part is a single "-"
+pub struct History {
+    path: String,
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Run {
+    pub id: usize,
+    pub timestamp: u64,
+    pub commit: String,
+    pub tag: Option<String>,
+    pub days: Vec<DayTiming>,
+}
+
+// parts are None when they were not run or did not succeed
+#[derive(Debug, PartialEq, Clone)]
+pub struct DayTiming {
+    pub number: usize,
+    pub load: Duration,
+    pub first: Option<Duration>,
+    pub second: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Change {
+    pub before: Duration,
+    pub after: Duration,
+}
+
+// changes are None unless both runs have a timing for the part
+#[derive(Debug, PartialEq, Clone)]
+pub struct DayComparison {
+    pub number: usize,
+    pub first: Option<Change>,
+    pub second: Option<Change>,
+}
+
+impl History {
+    // where to keep the history when it is not given explicitly
+    pub const FILE_VAR: &'static str = "AOC_HISTORY_FILE";
+
+    // a missing file is just an empty history
+    pub fn load(path: &str) -> Result<Self> {
+        let content = match read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(Error::Io {
+                    path: path.to_string(),
+                    kind: e.kind(),
+                })
+            }
+        };
+
+        Ok(Self {
+            path: path.to_string(),
+            runs: Self::parse(&content)?,
+        })
+    }
+
+    fn parse(s: &str) -> Result<Vec<Run>> {
+        let lines = parse::lines(s, 1, |line| {
+            if line.trim().is_empty() {
+                return Ok(None);
+            }
+
+            let tokens = line.split(' ').collect::<Vec<_>>();
+            if tokens.len() != 8 {
+                return Err(LineError::new(1, "expected 8 fields"));
+            }
+
+            let run = Run {
+                id: parse::number(line, tokens[0])?,
+                timestamp: parse::number(line, tokens[1])?,
+                commit: tokens[2].to_string(),
+                tag: Some(tokens[3]).filter(|&tag| tag != "-").map(String::from),
+                days: vec![DayTiming {
+                    number: parse::number(line, tokens[4])?,
+                    load: parse_nanos(line, tokens[5])?.unwrap_or_default(),
+                    first: parse_nanos(line, tokens[6])?,
+                    second: parse_nanos(line, tokens[7])?,
+                }],
+            };
+
+            Ok(Some(run))
+        })?;
+
+        // consecutive lines of the same run are merged back together
+        let mut runs: Vec<Run> = Vec::new();
+        for mut line in lines.into_iter().flatten() {
+            match runs.last_mut() {
+                Some(run) if run.id == line.id => run.days.append(&mut line.days),
+                _ => runs.push(line),
+            }
+        }
+
+        Ok(runs)
+    }
+
+    pub fn last(&self) -> Option<&Run> {
+        self.runs.last()
+    }
+
+    // the latest run with the given tag
+    pub fn tagged(&self, tag: &str) -> Option<&Run> {
+        self.runs
+            .iter()
+            .rev()
+            .find(|run| run.tag.as_deref() == Some(tag))
+    }
+
+    // the run that comes after the last one, not recorded yet
+    pub fn next_run(&self, report: &Report, commit: &str, tag: Option<&str>) -> Run {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        Run {
+            id: self.last().map_or(1, |run| run.id + 1),
+            timestamp,
+            commit: commit.to_string(),
+            tag: tag.map(String::from),
+            // cached answers took no time to find, the other part and the load did
+            days: report
+                .days
+                .iter()
+                .filter(|day| !day.is_cached())
+                .map(DayTiming::from)
+                .collect(),
+        }
+    }
+
+    pub fn record(&mut self, run: Run) -> Result<()> {
+        let io_error = |e: std::io::Error| Error::Write {
+            path: self.path.clone(),
+            kind: e.kind(),
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(io_error)?;
+        file.write_all(run.to_string().as_bytes())
+            .map_err(io_error)?;
+
+        self.runs.push(run);
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Run {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let nanos = |elapsed: Option<Duration>| {
+            elapsed.map_or("-".to_string(), |elapsed| elapsed.as_nanos().to_string())
+        };
+
+        for day in self.days.iter() {
+            writeln!(
+                f,
+                "{} {} {} {} {} {} {} {}",
+                self.id,
+                self.timestamp,
+                self.commit,
+                self.tag.as_deref().unwrap_or("-"),
+                day.number,
+                day.load.as_nanos(),
+                nanos(day.first),
+                nanos(day.second)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_nanos(line: &str, token: &str) -> LineResult<Option<Duration>> {
+    match token {
+        "-" => Ok(None),
+        token => Ok(Some(Duration::from_nanos(parse::number(line, token)?))),
+    }
+}
+
+// benchmarked phases are represented by their median, which is less noisy
+impl From<&DayResult> for DayTiming {
+    fn from(day: &DayResult) -> Self {
+        let timing = |elapsed: Duration, stats: &Option<Stats>| {
+            stats.as_ref().map_or(elapsed, |stats| stats.median)
+        };
+        let part = |result: bool, elapsed, stats| Some(timing(elapsed, stats)).filter(|_| result);
+
+        Self {
+            number: day.number,
+            load: timing(day.load_elapsed, &day.load_stats),
+            first: part(
+                matches!(day.first_result, Some(Ok(_))) && !day.first_cached,
+                day.first_elapsed,
+                &day.first_stats,
+            ),
+            second: part(
+                matches!(day.second_result, Some(Ok(_))) && !day.second_cached,
+                day.second_elapsed,
+                &day.second_stats,
+            ),
+        }
+    }
+}
+
+impl Change {
+    // relative change, e.g. 0.25 when 25% slower
+    pub fn change(&self) -> f64 {
+        self.after.as_secs_f64() / self.before.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0
+    }
+
+    pub fn is_slower(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+impl DayComparison {
+    pub fn is_slower(&self, threshold: f64) -> bool {
+        [self.first, self.second]
+            .iter()
+            .flatten()
+            .any(|change| change.is_slower(threshold))
+    }
+}
+
+// one comparison per day of the current run
+pub fn compare(baseline: &Run, current: &Run) -> Vec<DayComparison> {
+    current
+        .days
+        .iter()
+        .map(|day| {
+            let before = baseline
+                .days
+                .iter()
+                .find(|before| before.number == day.number);
+            let change = |before: Option<Duration>, after: Option<Duration>| {
+                Some(Change {
+                    before: before?,
+                    after: after?,
+                })
+            };
+
+            DayComparison {
+                number: day.number,
+                first: change(before.and_then(|before| before.first), day.first),
+                second: change(before.and_then(|before| before.second), day.second),
+            }
+        })
+        .collect()
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(number: usize, first: Option<u64>, second: Option<u64>) -> DayTiming {
+        DayTiming {
+            number,
+            load: Duration::from_nanos(10),
+            first: first.map(Duration::from_nanos),
+            second: second.map(Duration::from_nanos),
+        }
+    }
+
+    fn run(id: usize, tag: Option<&str>, days: Vec<DayTiming>) -> Run {
+        Run {
+            id,
+            timestamp: 1608854400,
+            commit: "abc1234".to_string(),
+            tag: tag.map(String::from),
+            days,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let runs = vec![
+            run(
+                1,
+                None,
+                vec![timing(1, Some(100), None), timing(2, None, None)],
+            ),
+            run(2, Some("fast"), vec![timing(1, Some(50), Some(70))]),
+        ];
+        let content = runs.iter().map(Run::to_string).collect::<String>();
+        assert_eq!(
+            content.lines().next(),
+            Some("1 1608854400 abc1234 - 1 10 100 -")
+        );
+        assert_eq!(History::parse(&content).unwrap(), runs);
+
+        let error = History::parse("1 2 abc - 3 4 x -").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "parse error at 1:15: invalid number 'x': invalid digit found in string"
+        );
+        assert!(History::parse("1 2 abc - 3 4").is_err());
+    }
+
+    #[test]
+    fn test_next_run() {
+        let day = |number, first_cached, second_cached| DayResult {
+            number,
+            load_elapsed: Duration::from_nanos(10),
+            load_stats: None,
+            load_memory: None,
+            first_result: Some(Ok(1.into())),
+            first_elapsed: Duration::from_nanos(if first_cached { 0 } else { 20 }),
+            first_stats: None,
+            first_memory: None,
+            first_verdict: None,
+            first_cached,
+            second_result: Some(Ok(2.into())),
+            second_elapsed: Duration::from_nanos(if second_cached { 0 } else { 30 }),
+            second_stats: None,
+            second_memory: None,
+            second_verdict: None,
+            second_cached,
+            cpu_elapsed: Duration::from_nanos(60),
+        };
+        let report = Report {
+            days: vec![
+                day(1, true, false),
+                day(2, true, true),
+                day(3, false, false),
+            ],
+            elapsed: Duration::from_nanos(60),
+            benchmark: false,
+        };
+
+        let history = History {
+            path: String::new(),
+            runs: vec![],
+        };
+        let run = history.next_run(&report, "abc1234", None);
+        assert_eq!(run.id, 1);
+        assert_eq!(
+            run.days,
+            vec![timing(1, None, Some(30)), timing(3, Some(20), Some(30))]
+        );
+    }
+
+    #[test]
+    fn test_tagged() {
+        let history = History {
+            path: String::new(),
+            runs: vec![
+                run(1, Some("base"), vec![]),
+                run(2, Some("base"), vec![]),
+                run(3, None, vec![]),
+            ],
+        };
+        assert_eq!(history.tagged("base").map(|run| run.id), Some(2));
+        assert_eq!(history.tagged("other"), None);
+        assert_eq!(history.last().map(|run| run.id), Some(3));
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = run(
+            1,
+            None,
+            vec![timing(1, Some(100), Some(100)), timing(2, Some(10), None)],
+        );
+        let current = run(
+            2,
+            None,
+            vec![timing(1, Some(105), Some(150)), timing(3, Some(1), None)],
+        );
+
+        let comparison = compare(&baseline, &current);
+        assert_eq!(comparison.len(), 2);
+        assert!(!comparison[0].first.unwrap().is_slower(0.1));
+        assert!(comparison[0].second.unwrap().is_slower(0.1));
+        assert!((comparison[0].second.unwrap().change() - 0.5).abs() < 1e-9);
+        assert!(comparison[0].is_slower(0.1));
+        assert!(!comparison[0].is_slower(0.5));
+        assert_eq!(
+            comparison[1],
+            DayComparison {
+                number: 3,
+                first: None,
+                second: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Advent of Code 2020 solvers, usable without the command line frontend.
+//!
+//! ```
+//! use aoc2020::{solve, Part};
+//!
+//! let answer = solve(1, Part::First, "1721\n979\n366\n299\n675\n1456").unwrap();
+//! assert_eq!(answer, "514579");
+//! ```
+
+#[macro_use]
+extern crate lazy_static;
+
+pub mod answer;
+pub mod answers;
+pub mod cache;
+pub mod cancel;
+pub mod crosscheck;
+pub mod days;
+pub mod error;
+pub mod examples;
+pub mod frame;
+pub mod history;
+pub mod memory;
+pub mod output;
+pub mod repl;
+pub mod report;
+pub mod stats;
+pub mod watch;
+
+pub use answer::Answer;
+pub use days::{Advent, Day, DayEntry, Part, DAY_NUMBERS};
+pub use error::{Error, Result};
+pub use report::{build_report, DayResult, Options, Report};
+
+// count the memory of the days measured by the tests too
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
+// solve a single part of a day straight from its input
+pub fn solve(number: usize, part: Part, input: &str) -> Result<Answer> {
+    days::entry(number)
+        .ok_or(Error::UnknownDay(number))?
+        .solve(part, input)
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let input = "1721\n979\n366\n299\n675\n1456";
+        assert_eq!(solve(1, Part::Second, input).unwrap(), "241861950");
+        assert_eq!(
+            solve(1, Part::First, "x").unwrap_err().to_string(),
+            "parse error at 1:1: invalid number 'x': invalid digit found in string"
+        );
+        assert_eq!(solve(42, Part::First, input), Err(Error::UnknownDay(42)));
+    }
+}
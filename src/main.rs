@@ -1,141 +1,456 @@
-use std::path::Path;
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
 
-use days::Advent;
-use output::{build_table, MERRY_CHRISTMAS};
-use report::build_report;
+use aoc2020::{
+    answers::{Answers, Verdict},
+    build_report,
+    cache::Cache,
+    crosscheck::cross_check,
+    days,
+    error::Error,
+    examples::run_examples,
+    frame,
+    history::{self, History, Run},
+    memory::CountingAllocator,
+    output::{
+        build_comparison_summary, build_comparison_table, build_cross_check_summary,
+        build_cross_check_table, build_csv, build_diagnostics, build_examples_summary,
+        build_examples_table, build_json, build_markdown, build_summary, build_table,
+        MERRY_CHRISTMAS,
+    },
+    repl::{Repl, Step},
+    report::Benchmark,
+    watch::Watcher,
+    Advent, Options, Part, DAY_NUMBERS,
+};
+use cli::{Command, Format, RunArgs, USAGE};
 
-#[macro_use]
-extern crate lazy_static;
+mod cli;
 
-mod days;
+const WATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
-    println!("{}", MERRY_CHRISTMAS);
+    let command = cli::parse_args(std::env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    match command {
+        Command::Run(args) => run(*args),
+        Command::List => list(),
+        Command::Help => println!("{}", USAGE),
+    }
+}
+
+fn run(args: RunArgs) {
+    let days = args.days.unwrap_or_else(|| DAY_NUMBERS.clone());
+
+    if args.example {
+        return run_examples_table(&days, args.part);
+    }
+
+    let mut advent = match args.input_dir {
+        Some(input_dir) => Advent::new(input_dir),
+        None => Advent::from_env().unwrap_or_else(|| Advent::new(src_path("input"))),
+    };
+
+    match args.input_file.as_deref() {
+        Some("-") => advent = advent.with_input(days[0], read_stdin()),
+        Some(path) => advent = advent.with_input_file(days[0], path.to_string()),
+        None => (),
+    }
+
+    if args.check {
+        return check(&days, &advent);
+    }
+
+    if args.cross_check {
+        return run_cross_check(&days, &advent, args.part);
+    }
+
+    if args.repl {
+        return repl(days[0], advent);
+    }
+
+    if args.visualize {
+        let output_dir = args.output_dir.unwrap_or_else(|| "visualize".to_string());
+        return visualize(days[0], &advent, args.part, Path::new(&output_dir));
+    }
+
+    let warmup = args.warmup.unwrap_or(1);
+    let benchmark = args.bench.map(|runs| Benchmark { runs, warmup });
+
+    // timings of cached answers would mean nothing
+    let use_cache = !args.no_cache && benchmark.is_none() && !args.compare;
+    let cache = Some(load_cache(args.clear_cache)).filter(|_| use_cache);
+
+    let options = Options {
+        part: args.part,
+        benchmark,
+        jobs: args.jobs,
+        day_timeout: args.day_timeout,
+        part_timeout: args.part_timeout,
+        cache,
+    };
+
+    if args.watch {
+        return watch(days[0], advent, &options, args.answers_file);
+    }
 
-    let day = std::env::args()
-        .nth(1)
-        .map(|s| s.parse::<usize>().expect("Invalid number"));
+    // fail before running anything
+    let answers = if args.verify {
+        Some(load_answers(args.answers_file))
+    } else {
+        None
+    };
 
-    let advent = Advent::new(input_folder());
-    let report = match day {
-        Some(number) => build_report(advent, number..=number),
-        _ => build_report(advent, Advent::DAY_NUMBERS),
+    let mut history = load_history(args.history_file);
+    let baseline = if args.compare {
+        Some(find_baseline(&history, args.baseline.as_deref()))
+    } else {
+        None
     };
 
-    build_table(&report)
+    let mut report = build_report(advent, &days, &options);
+    save_cache(&options);
+
+    if let Some(answers) = &answers {
+        report.days.iter_mut().for_each(|day| answers.verify(day));
+    }
+
+    match args.format {
+        Format::Table => {
+            println!("{}", MERRY_CHRISTMAS);
+            build_table(&report)
+                .print_stdout()
+                .expect("Error printing results");
+            println!("{}", build_summary(&report));
+        }
+        Format::Json => print!("{}", build_json(&report)),
+        Format::Csv => print!("{}", build_csv(&report)),
+        Format::Markdown => print!("{}", build_markdown(&report)),
+    }
+
+    let run = history.next_run(&report, &git_commit(), args.tag.as_deref());
+
+    let mut slower = false;
+    if let Some(baseline) = &baseline {
+        let threshold = args.threshold.unwrap_or(0.1);
+        let comparison = history::compare(baseline, &run);
+
+        build_comparison_table(&comparison, threshold)
+            .print_stdout()
+            .expect("Error printing results");
+        println!(
+            "{}",
+            build_comparison_summary(baseline, &comparison, threshold)
+        );
+
+        slower = comparison.iter().any(|day| day.is_slower(threshold));
+    }
+
+    // losing the timings is no reason to fail the run
+    if let Err(error) = history.record(run) {
+        eprintln!("cannot record the run: {}", error);
+    }
+
+    let regressed = report.days.iter().any(|day| {
+        [&day.first_verdict, &day.second_verdict]
+            .iter()
+            .any(|verdict| matches!(verdict, Some(Verdict::Wrong { .. })))
+    });
+
+    if regressed || slower {
+        process::exit(1);
+    }
+}
+
+fn run_examples_table(days: &[usize], part: Option<Part>) {
+    let results = run_examples(days, part);
+
+    println!("{}", MERRY_CHRISTMAS);
+    build_examples_table(&results)
         .print_stdout()
         .expect("Error printing results");
+    println!("{}", build_examples_summary(&results));
+
+    if !results.iter().all(|result| result.is_correct()) {
+        process::exit(1);
+    }
+}
+
+// loads the inputs without running anything, a day that cannot be read counts as a problem
+fn check(days: &[usize], advent: &Advent) {
+    let mut problems = false;
+
+    for &number in days {
+        let checked = days::entry(number)
+            .ok_or(Error::UnknownDay(number))
+            .and_then(|entry| {
+                let input = advent.read_input(number)?;
+                Ok((entry.check(&input)?, input))
+            });
+
+        match checked {
+            Ok((errors, input)) => {
+                println!("{}", build_diagnostics(number, &input, &errors));
+                problems |= !errors.is_empty();
+            }
+            Err(error) => {
+                println!("day {:02}: {}", number, error);
+                problems = true;
+            }
+        }
+    }
+
+    if problems {
+        process::exit(1);
+    }
 }
 
-fn input_folder() -> String {
-    Path::new(file!())
-        .parent()
-        .unwrap()
-        .join("input")
-        .to_str()
-        .unwrap()
-        .into()
+fn run_cross_check(days: &[usize], advent: &Advent, part: Option<Part>) {
+    let checks = cross_check(advent, days, part);
+
+    println!("{}", MERRY_CHRISTMAS);
+    build_cross_check_table(&checks)
+        .print_stdout()
+        .expect("Error printing results");
+    println!("{}", build_cross_check_summary(&checks));
+
+    if !checks.iter().all(|check| check.agrees()) {
+        process::exit(1);
+    }
 }
 
-mod report {
-    use std::{
-        ops::RangeInclusive,
-        time::{Duration, Instant},
+// one directory of frames per part, e.g. visualize/day_11/part_2/frame_0001.ppm
+fn visualize(number: usize, advent: &Advent, part: Option<Part>, output_dir: &Path) {
+    let day = advent.load_day(number).unwrap_or_else(|error| {
+        eprintln!("day {:02}: {}", number, error);
+        process::exit(1);
+    });
+
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![Part::First, Part::Second],
     };
 
-    use crate::days::Advent;
+    for part in parts {
+        let part_number = if part == Part::First { 1 } else { 2 };
+        let dir = output_dir
+            .join(format!("day_{:02}", number))
+            .join(format!("part_{}", part_number));
 
-    pub struct DayResult {
-        pub number: usize,
-        pub load_elapsed: Duration,
-        pub first_result: String,
-        pub first_elapsed: Duration,
-        pub second_result: String,
-        pub second_elapsed: Duration,
-    }
+        let frames = day.visualize(part).unwrap_or_else(|error| {
+            eprintln!("day {:02} part {}: {}", number, part_number, error);
+            process::exit(1);
+        });
 
-    pub type Report = Vec<DayResult>;
-    pub type ReportSlice<'a> = &'a [DayResult];
+        if frames.is_empty() {
+            println!("day {:02} part {}: nothing to show", number, part_number);
+            continue;
+        }
 
-    pub fn build_report(advent: Advent, day_numbers: RangeInclusive<usize>) -> Report {
-        day_numbers
-            .map(|number| build_day_result(&advent, number))
-            .collect()
+        let paths = frame::render(&frames, &dir).unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            process::exit(2);
+        });
+        println!(
+            "day {:02} part {}: {} frame(s) written to {}",
+            number,
+            part_number,
+            paths.len(),
+            dir.display()
+        );
     }
+}
+
+// one command per line until quit or the end of stdin
+fn repl(number: usize, advent: Advent) {
+    let mut repl = Repl::new(advent, number).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(2);
+    });
+    println!("{}", repl.status());
+
+    let stdin = io::stdin();
+    loop {
+        print!("{}", repl.prompt());
+        io::stdout().flush().expect("Error printing results");
 
-    macro_rules! elapsed {
-        ($expression:expr) => {{
-            let clock = Instant::now();
-            let result = $expression;
-            (result, clock.elapsed())
-        }};
+        let mut line = String::new();
+        match stdin.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => (),
+            Err(error) => {
+                eprintln!("cannot read stdin: {}", error);
+                process::exit(2);
+            }
+        }
+
+        match repl.execute(&line) {
+            Step::Print(output) if output.is_empty() => (),
+            Step::Print(output) => println!("{}", output),
+            Step::Quit => return,
+        }
     }
 
-    fn build_day_result(advent: &Advent, number: usize) -> DayResult {
-        let (day, load_elapsed) = elapsed!(advent.load_day(number));
-        let (first_result, first_elapsed) = elapsed!(day.first_challenge());
-        let (second_result, second_elapsed) = elapsed!(day.second_challenge());
+    // Ctrl-D leaves the prompt hanging
+    println!();
+}
 
-        DayResult {
-            number,
-            first_result,
-            second_result,
-            load_elapsed,
-            first_elapsed,
-            second_elapsed,
+// runs the day again after every change of its files, until interrupted
+fn watch(number: usize, advent: Advent, options: &Options, answers_file: Option<String>) {
+    let mut paths = advent.input_path(number).into_iter().collect::<Vec<_>>();
+    paths.extend(answers_file.iter().map(PathBuf::from));
+    let mut watcher = Watcher::new(paths);
+
+    println!("{}", MERRY_CHRISTMAS);
+    for run in 1.. {
+        let mut report = build_report(advent.clone(), &[number], options);
+        save_cache(options);
+
+        // a half written answers file is no reason to stop watching
+        if let Some(path) = &answers_file {
+            match Answers::load(path) {
+                Ok(answers) => report.days.iter_mut().for_each(|day| answers.verify(day)),
+                Err(error) => eprintln!("{}", error),
+            }
         }
+
+        println!("run {}", run);
+        build_table(&report)
+            .print_stdout()
+            .expect("Error printing results");
+        println!("watching for changes, press Ctrl-C to stop");
+
+        watcher.wait(WATCH_INTERVAL);
     }
 }
 
-mod output {
-    use cli_table::{Cell, Row, Table};
+// a broken cache is no reason to fail the run, it is rebuilt from scratch
+fn load_cache(clear: bool) -> Cache {
+    let path = env::var(Cache::FILE_VAR).unwrap_or_else(|_| source_path("cache.txt"));
+    let version = binary_version();
 
-    use crate::report::{DayResult, ReportSlice};
+    let cache = Cache::load(&path, &version).unwrap_or_else(|error| {
+        eprintln!("ignoring the cache: {}", error);
+        Cache::new(&path, &version)
+    });
 
-    pub fn build_table(report: ReportSlice) -> Table {
-        let mut rows = vec![build_header()];
-        rows.extend(report.iter().map(build_row));
-        Table::new(rows, Default::default()).unwrap()
+    if clear {
+        cache.clear().unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            process::exit(2);
+        });
     }
 
-    fn build_header() -> Row {
-        Row::new(vec![
-            Cell::new("day", Default::default()),
-            Cell::new("load_elapsed", Default::default()),
-            Cell::new("first_result", Default::default()),
-            Cell::new("first_elapsed", Default::default()),
-            Cell::new("second_result", Default::default()),
-            Cell::new("second_elapsed", Default::default()),
-        ])
+    cache
+}
+
+fn save_cache(options: &Options) {
+    if let Some(Err(error)) = options.cache.as_ref().map(Cache::save) {
+        eprintln!("cannot save the cache: {}", error);
     }
+}
 
-    fn build_row(day: &DayResult) -> Row {
-        Row::new(vec![
-            Cell::new(&format!("{:02}", &day.number), Default::default()),
-            Cell::new(&format!("{:?}", &day.load_elapsed), Default::default()),
-            Cell::new(&day.first_result.to_string(), Default::default()),
-            Cell::new(&format!("{:?}", &day.first_elapsed), Default::default()),
-            Cell::new(&day.second_result.to_string(), Default::default()),
-            Cell::new(&format!("{:?}", &day.second_elapsed), Default::default()),
-        ])
+// any rebuild may change the answers, so the executable itself is the version
+fn binary_version() -> String {
+    let executable = std::env::current_exe().and_then(fs::read);
+    match executable {
+        Ok(bytes) => {
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            format!("{}-{:016x}", env!("CARGO_PKG_VERSION"), hasher.finish())
+        }
+        Err(_) => env!("CARGO_PKG_VERSION").to_string(),
     }
+}
+
+fn load_history(path: Option<String>) -> History {
+    let path = path
+        .or_else(|| env::var(History::FILE_VAR).ok())
+        .unwrap_or_else(|| source_path("history.txt"));
+    History::load(&path).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(2);
+    })
+}
 
-    pub const MERRY_CHRISTMAS: &str = r"
+// the previous run unless a tag is given
+fn find_baseline(history: &History, tag: Option<&str>) -> Run {
+    let baseline = match tag {
+        Some(tag) => history.tagged(tag),
+        None => history.last(),
+    };
 
-                  ,---.  ,---.  ,---.  .-.   .-.                                 
-                  |\    /| | .-'  | .-.\ | .-.\  \ \_/ )/                                 
-                  |(\  / | | `-.  | `-'/ | `-'/   \   (_)                                 
-                  (_)\/  | | .-'  |   (  |   (     ) (                                    
-                  | \  / | |  `--.| |\ \ | |\ \    | |                                    
-                  | |\/| | /( __.'|_| \)\|_| \)\  /(_|                                    
-                  '-'  '-'(__)        (__)   (__)(__)                                     
-         ,--,  .-. .-.,---.  .-. .-.   .---.  _______           .--.     .---. 
-       .' .')  | | | || .-.\ | | | |  ( .-._)|__   __||\    /| / /\ \   ( .-._)
-       |  |(_) | `-' || `-'/ | | | | (_) \     )| |   |(\  / |/ /__\ \ (_) \   
-       \  \    | .-. ||   (  | | | | _  \ \   (_) |   (_)\/  ||  __  | _  \ \  
-        \  `-. | | |)|| |\ \ | `-')|( `-'  )    | |   | \  / || |  |)|( `-'  ) 
-         \____\/(  (_)|_| \)\`---(_) `----'     `-'   | |\/| ||_|  (_) `----'  
-              (__)        (__)                        '-'  '-'                 
+    baseline.cloned().unwrap_or_else(|| {
+        match tag {
+            Some(tag) => eprintln!("no run tagged '{}' to compare with", tag),
+            None => eprintln!("no previous run to compare with"),
+        }
+        process::exit(2);
+    })
+}
+
+// short hash of the checked out commit, if any
+fn git_commit() -> String {
+    process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn load_answers(path: Option<String>) -> Answers {
+    let path = path.unwrap_or_else(|| src_path("answers.txt"));
+    Answers::load(&path).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(2);
+    })
+}
+
+fn list() {
+    for &number in DAY_NUMBERS.iter() {
+        let title = days::entry(number).map_or("", |entry| entry.title);
+        println!("day {:02}  {}", number, title);
+    }
+}
+
+fn read_stdin() -> String {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .unwrap_or_else(|error| {
+            eprintln!("cannot read stdin: {}", error);
+            process::exit(2);
+        });
+    input
+}
+
+fn src_path(name: &str) -> String {
+    source_path(&format!("src/{}", name))
+}
 
-    ";
+// fallback for running from the source tree, no matter the working directory, as long as the
+// tree the binary was built from is still around: otherwise the same paths are relative to
+// the working directory
+fn source_path(name: &str) -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let root = if root.is_dir() { root } else { Path::new("") };
+    root.join(name).to_str().unwrap().into()
 }
@@ -0,0 +1,133 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+// Counts the bytes allocated by each thread, so that days running in parallel
+// don't get in the way of each other. It does nothing unless installed with
+// `#[global_allocator] static ALLOCATOR: CountingAllocator = CountingAllocator;`
+pub struct CountingAllocator;
+
+// both in bytes: the highest amount of memory in use at the same time, on top of
+// what was already in use when the measure started, and the sum of all allocations
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Memory {
+    pub peak: usize,
+    pub total: usize,
+}
+
+// no destructors and no lazy initialization, allocating here would recurse forever
+thread_local! {
+    static LIVE: Cell<usize> = const { Cell::new(0) };
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+    static TOTAL: Cell<usize> = const { Cell::new(0) };
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+fn allocated(size: usize) {
+    INSTALLED.store(true, Ordering::Relaxed);
+
+    // `try_with` fails while the thread is being torn down, nothing to measure by then
+    let _ = LIVE.try_with(|live| {
+        live.set(live.get() + size);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(live.get())));
+    });
+    let _ = TOTAL.try_with(|total| total.set(total.get() + size));
+}
+
+// memory may be freed by another thread than the one that allocated it
+fn freed(size: usize) {
+    let _ = LIVE.try_with(|live| live.set(live.get().saturating_sub(size)));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        freed(layout.size());
+    }
+
+    // counted as a brand new allocation, which is what it costs in the worst case
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            freed(layout.size());
+            allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+// None when the counting allocator is not installed
+pub fn measure<T, F>(f: F) -> (T, Option<Memory>)
+where
+    F: FnOnce() -> T,
+{
+    let live = LIVE.with(Cell::get);
+    let outer_peak = PEAK.with(|peak| peak.replace(live));
+    let total = TOTAL.with(Cell::get);
+
+    let result = f();
+
+    let peak = PEAK.with(|peak| peak.replace(outer_peak.max(peak.get())));
+    let memory = Memory {
+        peak: peak - live,
+        total: TOTAL.with(Cell::get) - total,
+    };
+
+    (
+        result,
+        Some(memory).filter(|_| INSTALLED.load(Ordering::Relaxed)),
+    )
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let kept = vec![0u8; 1000];
+
+        let ((), memory) = measure(|| {
+            let first = vec![0u8; 4000];
+            drop(first);
+            let second = vec![0u8; 3000];
+            drop(second);
+        });
+        let memory = memory.unwrap();
+        assert_eq!(memory.peak, 4000);
+        assert_eq!(memory.total, 7000);
+
+        let (nested, outer) = measure(|| {
+            let inner = measure(|| vec![0u8; 2000]);
+            let more = vec![0u8; 500];
+            (inner, more)
+        });
+        let ((vector, inner), more) = nested;
+        assert_eq!(inner.unwrap().peak, 2000);
+        assert!(outer.unwrap().peak >= 2500);
+        assert!(outer.unwrap().total >= 2500);
+
+        drop((kept, vector, more));
+    }
+}
@@ -0,0 +1,1075 @@
+use std::time::Duration;
+
+use cli_table::{Cell, Row, Table};
+
+use crate::{
+    answers::Verdict,
+    crosscheck::CrossCheck,
+    days::{Answer, Part},
+    error::{Error, ParseError, Result},
+    examples::ExampleResult,
+    history::{Change, DayComparison, Run},
+    memory::Memory,
+    report::{DayResult, Report},
+    stats::Stats,
+};
+
+const HEADER: [&str; 10] = [
+    "day",
+    "load_elapsed",
+    "load_memory",
+    "first_result",
+    "first_elapsed",
+    "first_memory",
+    "second_result",
+    "second_elapsed",
+    "second_memory",
+    "cpu_time",
+];
+
+pub fn build_table(report: &Report) -> Table {
+    let mut rows = vec![build_row(&HEADER)];
+    rows.extend(
+        report
+            .days
+            .iter()
+            .map(|day| build_row(&format_day(day, report.benchmark))),
+    );
+    Table::new(rows, Default::default()).unwrap()
+}
+
+// the wall clock is what parallel runs save, the cpu time is what they cost
+pub fn build_summary(report: &Report) -> String {
+    let cached = report
+        .days
+        .iter()
+        .map(|day| day.first_cached as usize + day.second_cached as usize)
+        .sum::<usize>();
+
+    let summary = format!(
+        "wall clock {:?}, cpu time {:?}",
+        report.elapsed,
+        report.cpu_elapsed()
+    );
+
+    match cached {
+        0 => summary,
+        1 => format!("{}, 1 answer from the cache", summary),
+        cached => format!("{}, {} answers from the cache", summary, cached),
+    }
+}
+
+fn build_row<T: AsRef<str>>(cells: &[T]) -> Row {
+    let cells = cells
+        .iter()
+        .map(|cell| Cell::new(cell.as_ref(), Default::default()))
+        .collect();
+
+    Row::new(cells)
+}
+
+const EXAMPLES_HEADER: [&str; 5] = ["day", "part", "example", "result", "elapsed"];
+
+pub fn build_examples_table(results: &[ExampleResult]) -> Table {
+    let mut rows = vec![build_row(&EXAMPLES_HEADER)];
+    rows.extend(
+        results
+            .iter()
+            .map(|result| build_row(&format_example(result))),
+    );
+    Table::new(rows, Default::default()).unwrap()
+}
+
+pub fn build_examples_summary(results: &[ExampleResult]) -> String {
+    let correct = results.iter().filter(|result| result.is_correct()).count();
+    format!("{} of {} examples correct", correct, results.len())
+}
+
+const CROSS_CHECK_HEADER: [&str; 6] = [
+    "day",
+    "part",
+    "implementation",
+    "result",
+    "elapsed",
+    "speedup",
+];
+
+// one row per implementation, each alternative is checked against the default one
+pub fn build_cross_check_table(checks: &[CrossCheck]) -> Table {
+    let mut rows = vec![build_row(&CROSS_CHECK_HEADER)];
+    for check in checks {
+        rows.extend((0..check.results.len()).map(|i| build_row(&format_implementation(check, i))));
+    }
+    Table::new(rows, Default::default()).unwrap()
+}
+
+pub fn build_cross_check_summary(checks: &[CrossCheck]) -> String {
+    let agreeing = checks.iter().filter(|check| check.agrees()).count();
+    format!(
+        "implementations agree on {} of {} parts",
+        agreeing,
+        checks.len()
+    )
+}
+
+const COMPARISON_HEADER: [&str; 7] = [
+    "day",
+    "first_before",
+    "first_after",
+    "first_change",
+    "second_before",
+    "second_after",
+    "second_change",
+];
+
+// `threshold` is the relative change above which a part counts as slower
+pub fn build_comparison_table(comparison: &[DayComparison], threshold: f64) -> Table {
+    let mut rows = vec![build_row(&COMPARISON_HEADER)];
+    rows.extend(
+        comparison
+            .iter()
+            .map(|day| build_row(&format_comparison(day, threshold))),
+    );
+    Table::new(rows, Default::default()).unwrap()
+}
+
+pub fn build_comparison_summary(
+    baseline: &Run,
+    comparison: &[DayComparison],
+    threshold: f64,
+) -> String {
+    let slower = comparison
+        .iter()
+        .filter(|day| day.is_slower(threshold))
+        .count();
+
+    format!(
+        "compared with run {} ({}{}), {} of {} days slower by more than {}%",
+        baseline.id,
+        baseline.commit,
+        baseline
+            .tag
+            .as_ref()
+            .map_or(String::new(), |tag| format!(", {}", tag)),
+        slower,
+        comparison.len(),
+        threshold * 100.0
+    )
+}
+
+// one block per day, with each problem pointed out in the offending line
+pub fn build_diagnostics(number: usize, input: &str, errors: &[ParseError]) -> String {
+    if errors.is_empty() {
+        return format!("day {:02}: ok", number);
+    }
+
+    let mut lines = vec![format!(
+        "day {:02}: {} problem{}",
+        number,
+        errors.len(),
+        if errors.len() == 1 { "" } else { "s" }
+    )];
+
+    for error in errors {
+        lines.push(format!(
+            "  {}:{}: {}",
+            error.line, error.column, error.message
+        ));
+
+        // e.g. a missing section is reported past the end of the input
+        if let Some(line) = input.lines().nth(error.line.saturating_sub(1)) {
+            lines.push(format!("    {}", line));
+            lines.push(format!(
+                "    {}^",
+                " ".repeat(error.column.saturating_sub(1))
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+// same cells of the table, ready to be pasted in the README
+pub fn build_markdown(report: &Report) -> String {
+    let mut lines = vec![markdown_row(&HEADER), markdown_row(&["---"; HEADER.len()])];
+    lines.extend(
+        report
+            .days
+            .iter()
+            .map(|day| markdown_row(&format_day(day, report.benchmark))),
+    );
+    lines.push(String::new());
+    lines.push(build_summary(report));
+    lines.join("\n") + "\n"
+}
+
+fn markdown_row<T: AsRef<str>>(cells: &[T]) -> String {
+    let cells = cells
+        .iter()
+        .map(|cell| cell.as_ref().replace('|', "\\|").replace('\n', "<br>"))
+        .collect::<Vec<_>>();
+
+    format!("| {} |", cells.join(" | "))
+}
+
+fn format_day(day: &DayResult, benchmark: bool) -> Vec<String> {
+    let elapsed = |cached, elapsed, stats| format_cached(cached, elapsed, stats, benchmark);
+
+    vec![
+        format!("{:02}", &day.number),
+        elapsed(day.is_cached(), day.load_elapsed, &day.load_stats),
+        format_memory(&day.load_memory),
+        format_verdict(format_result(&day.first_result), &day.first_verdict),
+        elapsed(day.first_cached, day.first_elapsed, &day.first_stats),
+        format_memory(&day.first_memory),
+        format_verdict(format_result(&day.second_result), &day.second_verdict),
+        elapsed(day.second_cached, day.second_elapsed, &day.second_stats),
+        format_memory(&day.second_memory),
+        format!("{:?}", day.cpu_elapsed),
+    ]
+}
+
+fn format_example(result: &ExampleResult) -> Vec<String> {
+    let verdict = if result.is_correct() {
+        Verdict::Correct
+    } else {
+        Verdict::Wrong {
+            expected: result.example.expected.to_string(),
+        }
+    };
+
+    vec![
+        format!("{:02}", result.number),
+        format_part(result.example.part),
+        result.index.to_string(),
+        format_verdict(format_result(&Some(result.result.clone())), &Some(verdict)),
+        format!("{:?}", result.elapsed),
+    ]
+}
+
+// the first result is the default one
+fn format_implementation(check: &CrossCheck, i: usize) -> Vec<String> {
+    let (default, result) = (&check.results[0], &check.results[i]);
+    let displayed = format_result(&Some(result.result.clone()));
+
+    let verdict = if i == 0 {
+        None
+    } else if result.result == default.result {
+        Some(Verdict::Correct)
+    } else {
+        Some(Verdict::Wrong {
+            expected: format_result(&Some(default.result.clone())),
+        })
+    };
+
+    // how many times faster than the default one
+    let speedup =
+        default.elapsed.as_secs_f64() / result.elapsed.as_secs_f64().max(f64::MIN_POSITIVE);
+
+    vec![
+        format!("{:02}", check.number),
+        format_part(check.part),
+        result.name.to_string(),
+        format_verdict(displayed, &verdict),
+        format!("{:?}", result.elapsed),
+        format!("{:.2}x", speedup),
+    ]
+}
+
+fn format_comparison(day: &DayComparison, threshold: f64) -> Vec<String> {
+    let mut cells = vec![format!("{:02}", day.number)];
+    for change in [day.first, day.second].iter() {
+        cells.extend_from_slice(&format_change(change, threshold));
+    }
+    cells
+}
+
+fn format_change(change: &Option<Change>, threshold: f64) -> [String; 3] {
+    let change = match change {
+        Some(change) => change,
+        None => return ["-".to_string(), "-".to_string(), "-".to_string()],
+    };
+
+    let slower = if change.is_slower(threshold) {
+        " ✘ slower"
+    } else {
+        ""
+    };
+
+    [
+        format!("{:?}", change.before),
+        format!("{:?}", change.after),
+        format!("{:+.1}%{}", change.change() * 100.0, slower),
+    ]
+}
+
+fn format_part(part: Part) -> String {
+    match part {
+        Part::First => "1",
+        Part::Second => "2",
+    }
+    .to_string()
+}
+
+// benchmarked phases get one line per statistic, "-" for the ones without samples
+fn format_elapsed(elapsed: Duration, stats: &Option<Stats>, benchmark: bool) -> String {
+    let values = match stats {
+        Some(stats) => stats.values().iter().map(|d| format!("{:?}", d)).collect(),
+        None if benchmark => vec!["-".to_string(); Stats::NAMES.len()],
+        None => return format!("{:?}", elapsed),
+    };
+
+    Stats::NAMES
+        .iter()
+        .zip(values)
+        .map(|(name, value)| format!("{} {}", name, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// nothing was run, so there is nothing to time
+fn format_cached(
+    cached: bool,
+    elapsed: Duration,
+    stats: &Option<Stats>,
+    benchmark: bool,
+) -> String {
+    if cached {
+        "cached".to_string()
+    } else {
+        format_elapsed(elapsed, stats, benchmark)
+    }
+}
+
+// peak / total
+fn format_memory(memory: &Option<Memory>) -> String {
+    match memory {
+        Some(memory) => format!(
+            "{} / {}",
+            format_bytes(memory.peak),
+            format_bytes(memory.total)
+        ),
+        None => "-".to_string(),
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{}B", bytes),
+        _ => format!("{:.1}{}", value, UNITS[unit]),
+    }
+}
+
+fn format_result(result: &Option<Result<Answer>>) -> String {
+    match result {
+        Some(Ok(answer)) => answer.to_string(),
+        Some(Err(Error::Timeout)) => "TIMEOUT".to_string(),
+        Some(Err(error)) => format!("ERROR: {}", error),
+        None => "-".to_string(),
+    }
+}
+
+fn format_verdict(result: String, verdict: &Option<Verdict>) -> String {
+    match verdict {
+        Some(Verdict::Correct) => format!("{} ✔", result),
+        Some(Verdict::Wrong { expected }) => format!("{} ✘ expected {}", result, expected),
+        Some(Verdict::Unknown) | None => result,
+    }
+}
+
+/* machine readable exports */
+
+enum Value {
+    Number(i128),
+    Bool(bool),
+    Text(String),
+    Null,
+}
+
+type Fields = Vec<(String, Value)>;
+
+// durations are exported as nanoseconds, memory as bytes
+fn export_fields(day: &DayResult, benchmark: bool) -> Fields {
+    let verified = day.first_verdict.is_some() || day.second_verdict.is_some();
+
+    let mut fields = vec![("day".to_string(), Value::Number(day.number as i128))];
+    let load_elapsed = nanos(day.load_elapsed);
+    export_elapsed(
+        &mut fields,
+        "load",
+        load_elapsed,
+        &day.load_stats,
+        benchmark,
+    );
+    export_memory(&mut fields, "load", &day.load_memory);
+
+    let (result, kind, error, elapsed) = export_part(&day.first_result, day.first_elapsed);
+    fields.push(("first_result".to_string(), result));
+    fields.push(("first_kind".to_string(), kind));
+    fields.push(("first_error".to_string(), error));
+    fields.push(("first_cached".to_string(), Value::Bool(day.first_cached)));
+    if verified {
+        fields.push((
+            "first_verdict".to_string(),
+            export_verdict(&day.first_verdict),
+        ));
+    }
+    export_elapsed(&mut fields, "first", elapsed, &day.first_stats, benchmark);
+    export_memory(&mut fields, "first", &day.first_memory);
+
+    let (result, kind, error, elapsed) = export_part(&day.second_result, day.second_elapsed);
+    fields.push(("second_result".to_string(), result));
+    fields.push(("second_kind".to_string(), kind));
+    fields.push(("second_error".to_string(), error));
+    fields.push(("second_cached".to_string(), Value::Bool(day.second_cached)));
+    if verified {
+        fields.push((
+            "second_verdict".to_string(),
+            export_verdict(&day.second_verdict),
+        ));
+    }
+    export_elapsed(&mut fields, "second", elapsed, &day.second_stats, benchmark);
+    export_memory(&mut fields, "second", &day.second_memory);
+
+    fields.push(("cpu_elapsed_ns".to_string(), nanos(day.cpu_elapsed)));
+
+    fields
+}
+
+// benchmarked reports export every statistic, null when the phase has no samples
+fn export_elapsed(
+    fields: &mut Fields,
+    phase: &str,
+    elapsed: Value,
+    stats: &Option<Stats>,
+    benchmark: bool,
+) {
+    fields.push((format!("{}_elapsed_ns", phase), elapsed));
+
+    if !benchmark {
+        return;
+    }
+
+    for (i, name) in Stats::NAMES.iter().enumerate() {
+        let value = match stats {
+            Some(stats) => nanos(stats.values()[i]),
+            None => Value::Null,
+        };
+
+        fields.push((format!("{}_{}_ns", phase, name), value));
+    }
+}
+
+fn export_memory(fields: &mut Fields, phase: &str, memory: &Option<Memory>) {
+    let bytes = |bytes: fn(&Memory) -> usize| match memory {
+        Some(memory) => Value::Number(bytes(memory) as i128),
+        None => Value::Null,
+    };
+
+    fields.push((format!("{}_peak_bytes", phase), bytes(|memory| memory.peak)));
+    fields.push((
+        format!("{}_total_bytes", phase),
+        bytes(|memory| memory.total),
+    ));
+}
+
+// result, kind, error and elapsed of a part
+fn export_part(result: &Option<Result<Answer>>, elapsed: Duration) -> (Value, Value, Value, Value) {
+    match result {
+        Some(Ok(answer)) => (
+            export_answer(answer),
+            Value::Text(answer.kind().to_string()),
+            Value::Null,
+            nanos(elapsed),
+        ),
+        Some(Err(error)) => (
+            Value::Null,
+            Value::Null,
+            Value::Text(error.to_string()),
+            nanos(elapsed),
+        ),
+        None => (Value::Null, Value::Null, Value::Null, Value::Null),
+    }
+}
+
+// integers stay numbers, anything that does not fit is exported as text
+fn export_answer(answer: &Answer) -> Value {
+    match answer {
+        Answer::Integer(n) => Value::Number(*n as i128),
+        Answer::None => Value::Null,
+        answer => Value::Text(answer.to_string()),
+    }
+}
+
+fn export_verdict(verdict: &Option<Verdict>) -> Value {
+    let verdict = match verdict {
+        Some(Verdict::Correct) => "correct",
+        Some(Verdict::Wrong { .. }) => "wrong",
+        Some(Verdict::Unknown) => "unknown",
+        None => return Value::Null,
+    };
+
+    Value::Text(verdict.to_string())
+}
+
+fn nanos(duration: Duration) -> Value {
+    Value::Number(duration.as_nanos() as i128)
+}
+
+pub fn build_json(report: &Report) -> String {
+    let days = report
+        .days
+        .iter()
+        .map(|day| format!("    {}", json_object(export_fields(day, report.benchmark))))
+        .collect::<Vec<_>>();
+
+    let days = if days.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n  ]", days.join(",\n"))
+    };
+
+    format!(
+        "{{\n  \"elapsed_ns\": {},\n  \"cpu_elapsed_ns\": {},\n  \"days\": {}\n}}\n",
+        report.elapsed.as_nanos(),
+        report.cpu_elapsed().as_nanos(),
+        days
+    )
+}
+
+fn json_object(fields: Fields) -> String {
+    let fields = fields
+        .into_iter()
+        .map(|(key, value)| format!("{}: {}", json_string(&key), json_value(value)))
+        .collect::<Vec<_>>();
+
+    format!("{{{}}}", fields.join(", "))
+}
+
+fn json_value(value: Value) -> String {
+    match value {
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Text(text) => json_string(&text),
+        Value::Null => "null".to_string(),
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// one row per day, sum the cpu time for the total
+pub fn build_csv(report: &Report) -> String {
+    let mut lines = Vec::new();
+
+    for (i, day) in report.days.iter().enumerate() {
+        let fields = export_fields(day, report.benchmark);
+        if i == 0 {
+            lines.push(
+                fields
+                    .iter()
+                    .map(|(key, _)| key.clone())
+                    .collect::<Vec<_>>(),
+            );
+        }
+        lines.push(
+            fields
+                .into_iter()
+                .map(|(_, value)| csv_value(value))
+                .collect(),
+        );
+    }
+
+    lines
+        .iter()
+        .map(|line| line.join(",") + "\n")
+        .collect::<String>()
+}
+
+fn csv_value(value: Value) -> String {
+    match value {
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Text(text) if text.contains(&[',', '"', '\n'][..]) => {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        }
+        Value::Text(text) => text,
+        Value::Null => String::new(),
+    }
+}
+
+pub const MERRY_CHRISTMAS: &str = r"
+
+                  ,---.  ,---.  ,---.  .-.   .-.                                 
+                  |\    /| | .-'  | .-.\ | .-.\  \ \_/ )/                                 
+                  |(\  / | | `-.  | `-'/ | `-'/   \   (_)                                 
+                  (_)\/  | | .-'  |   (  |   (     ) (                                    
+                  | \  / | |  `--.| |\ \ | |\ \    | |                                    
+                  | |\/| | /( __.'|_| \)\|_| \)\  /(_|                                    
+                  '-'  '-'(__)        (__)   (__)(__)                                     
+         ,--,  .-. .-.,---.  .-. .-.   .---.  _______           .--.     .---. 
+       .' .')  | | | || .-.\ | | | |  ( .-._)|__   __||\    /| / /\ \   ( .-._)
+       |  |(_) | `-' || `-'/ | | | | (_) \     )| |   |(\  / |/ /__\ \ (_) \   
+       \  \    | .-. ||   (  | | | | _  \ \   (_) |   (_)\/  ||  __  | _  \ \  
+        \  `-. | | |)|| |\ \ | `-')|( `-'  )    | |   | \  / || |  |)|( `-'  ) 
+         \____\/(  (_)|_| \)\`---(_) `----'     `-'   | |\/| ||_|  (_) `----'  
+              (__)        (__)                        '-'  '-'                 
+
+    ";
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crosscheck::ImplementationResult;
+
+    fn report() -> Report {
+        let days = vec![
+            DayResult {
+                number: 1,
+                load_elapsed: Duration::from_nanos(1500),
+                first_result: Some(Ok(42.into())),
+                first_elapsed: Duration::from_nanos(20),
+                second_result: None,
+                second_elapsed: Duration::default(),
+                load_stats: None,
+                first_stats: None,
+                second_stats: None,
+                load_memory: Some(Memory {
+                    peak: 2048,
+                    total: 5000,
+                }),
+                first_memory: Some(Memory { peak: 0, total: 16 }),
+                second_memory: None,
+                first_verdict: None,
+                second_verdict: None,
+                first_cached: false,
+                second_cached: false,
+                cpu_elapsed: Duration::from_nanos(1520),
+            },
+            DayResult {
+                number: 12,
+                load_elapsed: Duration::from_micros(3),
+                first_result: Some(Err(Error::NotFound)),
+                first_elapsed: Duration::from_nanos(7),
+                second_result: Some(Ok("a \"b\", c".into())),
+                second_elapsed: Duration::from_nanos(8),
+                load_stats: None,
+                first_stats: None,
+                second_stats: None,
+                load_memory: None,
+                first_memory: None,
+                second_memory: None,
+                first_verdict: None,
+                second_verdict: None,
+                first_cached: false,
+                second_cached: false,
+                cpu_elapsed: Duration::from_nanos(3015),
+            },
+        ];
+
+        Report {
+            days,
+            elapsed: Duration::from_micros(2),
+            benchmark: false,
+        }
+    }
+
+    fn empty_report() -> Report {
+        Report {
+            days: vec![],
+            elapsed: Duration::ZERO,
+            benchmark: false,
+        }
+    }
+
+    fn benchmark_report() -> Report {
+        let samples = [1, 2, 3].iter().map(|&n| Duration::from_nanos(n));
+        let stats = Stats::from_samples(&samples.collect::<Vec<_>>());
+
+        let days = vec![DayResult {
+            number: 2,
+            load_elapsed: Duration::from_nanos(5),
+            first_result: Some(Ok(1.into())),
+            first_elapsed: Duration::from_nanos(6),
+            second_result: None,
+            second_elapsed: Duration::default(),
+            load_stats: stats,
+            first_stats: stats,
+            second_stats: None,
+            load_memory: None,
+            first_memory: None,
+            second_memory: None,
+            first_verdict: None,
+            second_verdict: None,
+            first_cached: false,
+            second_cached: false,
+            cpu_elapsed: Duration::from_nanos(11),
+        }];
+
+        Report {
+            days,
+            elapsed: Duration::from_nanos(11),
+            benchmark: true,
+        }
+    }
+
+    // a day that ran out of time is not benchmarked, but still gets the stats columns
+    fn timeout_report() -> Report {
+        let mut report = benchmark_report();
+        report.days.push(DayResult {
+            number: 15,
+            load_elapsed: Duration::from_nanos(4),
+            first_result: Some(Err(Error::Timeout)),
+            first_elapsed: Duration::from_nanos(100),
+            second_result: None,
+            second_elapsed: Duration::default(),
+            load_stats: None,
+            first_stats: None,
+            second_stats: None,
+            load_memory: None,
+            first_memory: None,
+            second_memory: None,
+            first_verdict: None,
+            second_verdict: None,
+            first_cached: false,
+            second_cached: false,
+            cpu_elapsed: Duration::from_nanos(104),
+        });
+        report
+    }
+
+    #[test]
+    fn test_json() {
+        let expected = r#"{
+  "elapsed_ns": 2000,
+  "cpu_elapsed_ns": 4535,
+  "days": [
+    {"day": 1, "load_elapsed_ns": 1500, "load_peak_bytes": 2048, "load_total_bytes": 5000, "first_result": 42, "first_kind": "integer", "first_error": null, "first_cached": false, "first_elapsed_ns": 20, "first_peak_bytes": 0, "first_total_bytes": 16, "second_result": null, "second_kind": null, "second_error": null, "second_cached": false, "second_elapsed_ns": null, "second_peak_bytes": null, "second_total_bytes": null, "cpu_elapsed_ns": 1520},
+    {"day": 12, "load_elapsed_ns": 3000, "load_peak_bytes": null, "load_total_bytes": null, "first_result": null, "first_kind": null, "first_error": "no solution found", "first_cached": false, "first_elapsed_ns": 7, "first_peak_bytes": null, "first_total_bytes": null, "second_result": "a \"b\", c", "second_kind": "text", "second_error": null, "second_cached": false, "second_elapsed_ns": 8, "second_peak_bytes": null, "second_total_bytes": null, "cpu_elapsed_ns": 3015}
+  ]
+}
+"#;
+        assert_eq!(build_json(&report()), expected);
+
+        let expected = "{\n  \"elapsed_ns\": 0,\n  \"cpu_elapsed_ns\": 0,\n  \"days\": []\n}\n";
+        assert_eq!(build_json(&empty_report()), expected);
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\\b\n\t"), r#""a\\b\n\u0009""#);
+    }
+
+    #[test]
+    fn test_csv() {
+        let expected = "day,load_elapsed_ns,load_peak_bytes,load_total_bytes,\
+                        first_result,first_kind,first_error,first_cached,first_elapsed_ns,first_peak_bytes,first_total_bytes,\
+                        second_result,second_kind,second_error,second_cached,second_elapsed_ns,second_peak_bytes,second_total_bytes,\
+                        cpu_elapsed_ns
+1,1500,2048,5000,42,integer,,false,20,0,16,,,,false,,,,1520
+12,3000,,,,,no solution found,false,7,,,\"a \"\"b\"\", c\",text,,false,8,,,3015
+";
+        assert_eq!(build_csv(&report()), expected);
+        assert_eq!(build_csv(&empty_report()), "");
+    }
+
+    #[test]
+    fn test_csv_benchmark() {
+        let csv = build_csv(&benchmark_report());
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "day,load_elapsed_ns,load_min_ns,load_median_ns,load_mean_ns,load_stddev_ns,load_p95_ns,load_peak_bytes,load_total_bytes,\
+             first_result,first_kind,first_error,first_cached,first_elapsed_ns,first_min_ns,first_median_ns,first_mean_ns,first_stddev_ns,first_p95_ns,first_peak_bytes,first_total_bytes,\
+             second_result,second_kind,second_error,second_cached,second_elapsed_ns,second_min_ns,second_median_ns,second_mean_ns,second_stddev_ns,second_p95_ns,second_peak_bytes,second_total_bytes,\
+             cpu_elapsed_ns"
+        );
+        assert_eq!(
+            lines[1],
+            "2,5,1,2,2,1,3,,,1,integer,,false,6,1,2,2,1,3,,,,,,false,,,,,,,,,11"
+        );
+    }
+
+    #[test]
+    fn test_csv_benchmark_timeout() {
+        let csv = build_csv(&timeout_report());
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            build_csv(&benchmark_report()).lines().next().unwrap()
+        );
+        assert_eq!(
+            lines[2],
+            "15,4,,,,,,,,,,TIMEOUT,false,100,,,,,,,,,,,false,,,,,,,,,104"
+        );
+
+        let json = build_json(&timeout_report());
+        assert!(json.contains(r#""day": 15, "load_elapsed_ns": 4, "load_min_ns": null,"#));
+        assert!(json.contains(r#""second_p95_ns": null, "second_peak_bytes": null"#));
+
+        let markdown = build_markdown(&timeout_report());
+        let stats = "min -<br>median -<br>mean -<br>stddev -<br>p95 -";
+        assert_eq!(
+            markdown.lines().nth(3),
+            Some(
+                format!(
+                    "| 15 | {} | - | TIMEOUT | {} | - | - | {} | - | 104ns |",
+                    stats, stats, stats
+                )
+                .as_str()
+            )
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let expected =
+            "| day | load_elapsed | load_memory | first_result | first_elapsed | first_memory | second_result | second_elapsed | second_memory | cpu_time |
+| --- | --- | --- | --- | --- | --- | --- | --- | --- | --- |
+| 01 | 1.5µs | 2.0KiB / 4.9KiB | 42 | 20ns | 0B / 16B | - | 0ns | - | 1.52µs |
+| 12 | 3µs | - | ERROR: no solution found | 7ns | - | a \"b\", c | 8ns | - | 3.015µs |
+
+wall clock 2µs, cpu time 4.535µs
+";
+        assert_eq!(build_markdown(&report()), expected);
+    }
+
+    #[test]
+    fn test_cached() {
+        let mut report = report();
+        report.days[0].first_cached = true;
+
+        let markdown = build_markdown(&report);
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[2],
+            "| 01 | cached | 2.0KiB / 4.9KiB | 42 | cached | 0B / 16B | - | 0ns | - | 1.52µs |"
+        );
+        assert_eq!(
+            lines[5],
+            "wall clock 2µs, cpu time 4.535µs, 1 answer from the cache"
+        );
+        assert!(build_csv(&report).contains(",integer,,true,"));
+    }
+
+    #[test]
+    fn test_verified() {
+        let mut report = report();
+        report.days[0].first_verdict = Some(Verdict::Correct);
+        report.days[1].first_verdict = Some(Verdict::Wrong {
+            expected: "1".to_string(),
+        });
+        report.days[1].second_verdict = Some(Verdict::Unknown);
+
+        let markdown = build_markdown(&report);
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[2],
+            "| 01 | 1.5µs | 2.0KiB / 4.9KiB | 42 ✔ | 20ns | 0B / 16B | - | 0ns | - | 1.52µs |"
+        );
+        assert_eq!(
+            lines[3],
+            "| 12 | 3µs | - | ERROR: no solution found ✘ expected 1 | 7ns | - | a \"b\", c | 8ns | - | 3.015µs |"
+        );
+
+        let csv = build_csv(&report);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "day,load_elapsed_ns,load_peak_bytes,load_total_bytes,\
+             first_result,first_kind,first_error,first_cached,first_verdict,first_elapsed_ns,first_peak_bytes,first_total_bytes,\
+             second_result,second_kind,second_error,second_cached,second_verdict,second_elapsed_ns,second_peak_bytes,second_total_bytes,\
+             cpu_elapsed_ns"
+        );
+        assert_eq!(
+            lines[1],
+            "1,1500,2048,5000,42,integer,,false,correct,20,0,16,,,,false,,,,,1520"
+        );
+        assert_eq!(
+            lines[2],
+            "12,3000,,,,,no solution found,false,wrong,7,,,\"a \"\"b\"\", c\",text,,false,unknown,8,,,3015"
+        );
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        assert_eq!(build_diagnostics(1, "1\n2", &[]), "day 01: ok");
+
+        let error = |line, column, message: &str| ParseError {
+            line,
+            column,
+            message: message.to_string(),
+        };
+        let errors = [error(2, 3, "invalid number"), error(4, 1, "missing tiles")];
+        assert_eq!(
+            build_diagnostics(20, "1\n2 x\n3", &errors),
+            "day 20: 2 problems
+  2:3: invalid number
+    2 x
+      ^
+  4:1: missing tiles"
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(format_bytes(0), "0B");
+        assert_eq!(format_bytes(1023), "1023B");
+        assert_eq!(format_bytes(1536), "1.5KiB");
+        assert_eq!(format_bytes(3 << 30), "3.0GiB");
+        assert_eq!(format_bytes(usize::MAX), "16777216.0TiB");
+    }
+
+    #[test]
+    fn test_markdown_benchmark() {
+        let markdown = build_markdown(&benchmark_report());
+        let stats = "min 1ns<br>median 2ns<br>mean 2ns<br>stddev 1ns<br>p95 3ns";
+        let empty = "min -<br>median -<br>mean -<br>stddev -<br>p95 -";
+        assert_eq!(
+            markdown.lines().nth(2),
+            Some(
+                format!(
+                    "| 02 | {} | - | 1 | {} | - | - | {} | - | 11ns |",
+                    stats, stats, empty
+                )
+                .as_str()
+            )
+        );
+    }
+
+    #[test]
+    fn test_examples() {
+        let example = |result| ExampleResult {
+            number: 1,
+            index: 2,
+            example: crate::days::Example::second("1\n2", "3"),
+            result,
+            elapsed: Duration::from_nanos(4),
+        };
+        let results = vec![example(Ok(3.into())), example(Err(Error::NotFound))];
+
+        assert_eq!(
+            format_example(&results[0]),
+            vec!["01", "2", "2", "3 ✔", "4ns"]
+        );
+        assert_eq!(
+            format_example(&results[1])[3],
+            "ERROR: no solution found ✘ expected 3"
+        );
+        assert_eq!(build_examples_summary(&results), "1 of 2 examples correct");
+    }
+
+    #[test]
+    fn test_cross_check() {
+        let result = |name, result, micros| ImplementationResult {
+            name,
+            result,
+            elapsed: Duration::from_micros(micros),
+        };
+        let checks = vec![
+            CrossCheck {
+                number: 13,
+                part: Part::Second,
+                results: vec![
+                    result("default", Ok(42.into()), 300),
+                    result("fast", Ok(42.into()), 100),
+                    result("wrong", Err(Error::NotFound), 600),
+                ],
+            },
+            CrossCheck {
+                number: 15,
+                part: Part::First,
+                results: vec![
+                    result("default", Ok(7.into()), 1),
+                    result("vector", Ok(7.into()), 1),
+                ],
+            },
+        ];
+
+        let rows = (0..3)
+            .map(|i| format_implementation(&checks[0], i))
+            .collect::<Vec<_>>();
+        assert_eq!(rows[0], vec!["13", "2", "default", "42", "300µs", "1.00x"]);
+        assert_eq!(rows[1][3..], ["42 ✔", "100µs", "3.00x"]);
+        assert_eq!(
+            rows[2][3..],
+            ["ERROR: no solution found ✘ expected 42", "600µs", "0.50x"]
+        );
+        assert_eq!(
+            build_cross_check_summary(&checks),
+            "implementations agree on 1 of 2 parts"
+        );
+    }
+
+    #[test]
+    fn test_comparison() {
+        let change = |before, after| {
+            Some(Change {
+                before: Duration::from_micros(before),
+                after: Duration::from_micros(after),
+            })
+        };
+        let comparison = vec![
+            DayComparison {
+                number: 3,
+                first: change(100, 95),
+                second: change(100, 150),
+            },
+            DayComparison {
+                number: 4,
+                first: change(10, 11),
+                second: None,
+            },
+        ];
+
+        assert_eq!(
+            format_comparison(&comparison[0], 0.1),
+            vec![
+                "03",
+                "100µs",
+                "95µs",
+                "-5.0%",
+                "100µs",
+                "150µs",
+                "+50.0% ✘ slower"
+            ]
+        );
+        assert_eq!(format_comparison(&comparison[1], 0.1)[4..], ["-", "-", "-"]);
+
+        let baseline = Run {
+            id: 7,
+            timestamp: 0,
+            commit: "abc1234".to_string(),
+            tag: Some("before".to_string()),
+            days: vec![],
+        };
+        assert_eq!(
+            build_comparison_summary(&baseline, &comparison, 0.1),
+            "compared with run 7 (abc1234, before), 1 of 2 days slower by more than 10%"
+        );
+    }
+}
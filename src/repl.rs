@@ -0,0 +1,289 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    days::{self, Advent, Day, DayEntry, Part},
+    error::{Error, Result},
+};
+
+pub const HELP: &str = "Commands:
+    run [1|2]           run both challenges, or only the first or the second one
+    time                timings of the last load and of the last runs
+    input [FILE]        show where the input comes from, or load the day again from FILE
+    reload              load the day again, e.g. after editing its input
+    queries             list the questions the day can answer
+    query NAME [ARGS]   ask the day something, e.g. 'query holders shiny gold' for day 7
+    help                print this message
+    quit                leave, Ctrl-D works too";
+
+// What the loop should do after a command
+#[derive(Debug, PartialEq)]
+pub enum Step {
+    Print(String),
+    Quit,
+}
+
+// A single day loaded once and poked at many times, see `Repl::execute`
+pub struct Repl {
+    advent: Advent,
+    entry: &'static DayEntry,
+    // the load error is kept to be shown by whatever needs the day
+    day: Result<Box<dyn Day>>,
+    timings: Timings,
+}
+
+#[derive(Default)]
+struct Timings {
+    load: Option<Duration>,
+    first: Option<Duration>,
+    second: Option<Duration>,
+    query: Option<(&'static str, Duration)>,
+}
+
+impl Repl {
+    pub fn new(advent: Advent, number: usize) -> Result<Self> {
+        let entry = days::entry(number).ok_or(Error::UnknownDay(number))?;
+        let mut repl = Self {
+            advent,
+            entry,
+            day: Err(Error::NotFound),
+            timings: Timings::default(),
+        };
+
+        repl.load();
+        Ok(repl)
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("day {:02}> ", self.entry.number)
+    }
+
+    // what happened to the last load
+    pub fn status(&self) -> String {
+        let name = format!("day {:02} {}", self.entry.number, self.entry.title);
+        match (&self.day, self.timings.load) {
+            (Ok(_), Some(elapsed)) => format!("{}: loaded in {:?}", name, elapsed),
+            (Err(error), _) => format!("{}: {}", name, error),
+            (Ok(_), None) => unreachable!("aaaah! a loaded day has a load time"),
+        }
+    }
+
+    pub fn execute(&mut self, line: &str) -> Step {
+        let line = line.trim();
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        let args = args.trim();
+
+        let output = match (command, args) {
+            ("", _) => String::new(),
+            ("quit", "") | ("exit", "") => return Step::Quit,
+            ("help", "") => HELP.to_string(),
+            ("run", "") => format!("{}\n{}", self.run(Part::First), self.run(Part::Second)),
+            ("run", "1") => self.run(Part::First),
+            ("run", "2") => self.run(Part::Second),
+            ("time", "") => self.timings(),
+            ("input", "") => self.input(),
+            ("input", path) => {
+                let advent = self.advent.clone();
+                self.advent = advent.with_input_file(self.entry.number, path.to_string());
+                self.load();
+                self.status()
+            }
+            ("reload", "") => {
+                self.load();
+                self.status()
+            }
+            ("queries", "") => self.queries(),
+            ("query", args) => {
+                let (name, args) = args.split_once(' ').unwrap_or((args, ""));
+                self.query(name, args)
+            }
+            _ => format!("unknown command '{}', try help", line),
+        };
+
+        Step::Print(output)
+    }
+
+    // a new input means the old timings are about something else
+    fn load(&mut self) {
+        let clock = Instant::now();
+        self.day = self.advent.load_day(self.entry.number);
+        self.timings = Timings {
+            load: Some(clock.elapsed()).filter(|_| self.day.is_ok()),
+            ..Timings::default()
+        };
+    }
+
+    fn run(&mut self, part: Part) -> String {
+        let day = match &self.day {
+            Ok(day) => day,
+            Err(error) => return format!("cannot run, {}", error),
+        };
+
+        let clock = Instant::now();
+        let result = part.solve(day.as_ref());
+        let elapsed = clock.elapsed();
+
+        let (number, timing) = match part {
+            Part::First => (1, &mut self.timings.first),
+            Part::Second => (2, &mut self.timings.second),
+        };
+        *timing = Some(elapsed);
+
+        match result {
+            Ok(answer) => format!("part {}: {} ({:?})", number, answer, elapsed),
+            Err(error) => format!("part {}: {} ({:?})", number, error, elapsed),
+        }
+    }
+
+    fn timings(&self) -> String {
+        let format = |elapsed: Option<Duration>| match elapsed {
+            Some(elapsed) => format!("{:?}", elapsed),
+            None => "-".to_string(),
+        };
+
+        let mut lines = vec![
+            format!("load    {}", format(self.timings.load)),
+            format!("part 1  {}", format(self.timings.first)),
+            format!("part 2  {}", format(self.timings.second)),
+        ];
+        if let Some((name, elapsed)) = self.timings.query {
+            lines.push(format!("{}  {:?}", name, elapsed));
+        }
+
+        lines.join("\n")
+    }
+
+    fn input(&self) -> String {
+        match self.advent.input_path(self.entry.number) {
+            Some(path) => format!("reading {}", path.display()),
+            None => "reading an input given as it is".to_string(),
+        }
+    }
+
+    fn queries(&self) -> String {
+        if self.entry.queries.is_empty() {
+            return format!("day {:02} has no queries", self.entry.number);
+        }
+
+        self.entry
+            .queries
+            .iter()
+            .map(|query| format!("query {} {}", query.name, query.args))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn query(&mut self, name: &str, args: &str) -> String {
+        let query = match self.entry.queries.iter().find(|query| query.name == name) {
+            Some(query) => query,
+            None => return format!("unknown query '{}', try queries", name),
+        };
+
+        let day = match &self.day {
+            Ok(day) => day,
+            Err(error) => return format!("cannot ask, {}", error),
+        };
+
+        let clock = Instant::now();
+        let result = (query.run)(day.as_ref(), args);
+        self.timings.query = Some((query.name, clock.elapsed()));
+
+        match result {
+            Ok(answer) => answer.to_string(),
+            Err(error) => error.to_string(),
+        }
+    }
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl(number: usize, input: &str) -> Repl {
+        let advent = Advent::new("src/input".to_string()).with_input(number, input.to_string());
+        Repl::new(advent, number).unwrap()
+    }
+
+    fn print(repl: &mut Repl, line: &str) -> String {
+        match repl.execute(line) {
+            Step::Print(output) => output,
+            Step::Quit => panic!("unexpected quit"),
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let mut repl = repl(1, "1721\n979\n366\n299\n675\n1456");
+        assert!(repl
+            .status()
+            .starts_with("day 01 Report Repair: loaded in "));
+        assert_eq!(repl.prompt(), "day 01> ");
+
+        assert!(print(&mut repl, "run 1").starts_with("part 1: 514579 ("));
+        assert!(print(&mut repl, "time").contains("part 2  -"));
+
+        let output = print(&mut repl, " run ");
+        assert!(output.contains("part 1: 514579") && output.contains("part 2: 241861950"));
+        assert!(!print(&mut repl, "time").contains('-'));
+
+        assert_eq!(print(&mut repl, ""), "");
+        assert!(print(&mut repl, "run 3").starts_with("unknown command"));
+        assert_eq!(repl.execute("quit"), Step::Quit);
+    }
+
+    #[test]
+    fn test_input() {
+        let mut repl = repl(1, "x");
+        assert!(repl
+            .status()
+            .ends_with("parse error at 1:1: invalid number 'x': invalid digit found in string"));
+        assert!(print(&mut repl, "run 1").starts_with("cannot run, parse error"));
+        assert_eq!(print(&mut repl, "input"), "reading an input given as it is");
+
+        assert!(print(&mut repl, "input src/input/day_01").contains("loaded in"));
+        assert!(print(&mut repl, "run 2").starts_with("part 2: "));
+        assert_eq!(print(&mut repl, "input"), "reading src/input/day_01");
+
+        let output = print(&mut repl, "input missing.txt");
+        assert_eq!(
+            output,
+            "day 01 Report Repair: cannot read missing.txt: entity not found"
+        );
+        assert!(print(&mut repl, "reload").ends_with("entity not found"));
+        assert!(print(&mut repl, "time").starts_with("load    -"));
+    }
+
+    #[test]
+    fn test_query() {
+        let input = "shiny gold bags contain 2 dark red bags.
+dark red bags contain 1 dark orange bag.
+dark orange bags contain no other bags.";
+        let mut repl = repl(7, input);
+
+        assert!(print(&mut repl, "queries").contains("query holders <color>"));
+        assert_eq!(print(&mut repl, "query contents shiny gold"), "2 dark red");
+        assert_eq!(
+            print(&mut repl, "query contents dark orange"),
+            "no other bags"
+        );
+        assert_eq!(print(&mut repl, "query holders dark orange"), "2");
+        assert_eq!(print(&mut repl, "query nested shiny gold"), "4");
+        assert_eq!(print(&mut repl, "query nested pink"), "no solution found");
+        assert!(print(&mut repl, "time").contains("nested  "));
+        assert!(print(&mut repl, "query wat").starts_with("unknown query 'wat'"));
+
+        let mut repl = self::repl(15, "0,3,6");
+        assert_eq!(print(&mut repl, "query spoken 2"), "3");
+        assert_eq!(print(&mut repl, "query spoken 10"), "0");
+        assert!(print(&mut repl, "query spoken 0").contains("turns start from 1"));
+        assert!(print(&mut repl, "query spoken 268435457").contains("turn too large"));
+        assert!(print(&mut repl, "query spoken 99999999999999").contains("turn too large"));
+
+        assert_eq!(
+            print(&mut self::repl(1, "1"), "queries"),
+            "day 01 has no queries"
+        );
+        assert!(Repl::new(Advent::new("src/input".to_string()), 42).is_err());
+    }
+}
@@ -0,0 +1,523 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use cpu_time::ThreadTime;
+
+use crate::{
+    answers::Verdict,
+    cache::Cache,
+    cancel::CancelToken,
+    days::{Advent, Answer, Part},
+    error::{Error, Result},
+    memory::{self, Memory},
+    stats::Stats,
+};
+
+// results are None when the challenge was not run at all,
+// stats are None unless the phase was benchmarked, verdicts unless the report was verified,
+// memory unless the phase was run to the end with the counting allocator installed.
+// Cached parts are not run at all, and neither is the load when both of them are cached
+pub struct DayResult {
+    pub number: usize,
+    pub load_elapsed: Duration,
+    pub load_stats: Option<Stats>,
+    pub load_memory: Option<Memory>,
+    pub first_result: Option<Result<Answer>>,
+    pub first_elapsed: Duration,
+    pub first_stats: Option<Stats>,
+    pub first_memory: Option<Memory>,
+    pub first_verdict: Option<Verdict>,
+    pub first_cached: bool,
+    pub second_result: Option<Result<Answer>>,
+    pub second_elapsed: Duration,
+    pub second_stats: Option<Stats>,
+    pub second_memory: Option<Memory>,
+    pub second_verdict: Option<Verdict>,
+    pub second_cached: bool,
+    // unlike the wall clock of each phase, not inflated by other days running in parallel
+    pub cpu_elapsed: Duration,
+}
+
+impl DayResult {
+    // the day was not even loaded, every part came from the cache
+    pub fn is_cached(&self) -> bool {
+        let cached = |result: &Option<Result<Answer>>, cached| cached || result.is_none();
+        (self.first_cached || self.second_cached)
+            && cached(&self.first_result, self.first_cached)
+            && cached(&self.second_result, self.second_cached)
+    }
+}
+
+// days are always in the requested order, however they were run
+pub struct Report {
+    pub days: Vec<DayResult>,
+    // wall clock
+    pub elapsed: Duration,
+    // every day gets the stats columns then, even the ones that could not be benchmarked
+    pub benchmark: bool,
+}
+
+impl Report {
+    pub fn cpu_elapsed(&self) -> Duration {
+        self.days.iter().map(|day| day.cpu_elapsed).sum()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Options {
+    // both parts when not set
+    pub part: Option<Part>,
+    // single run when not set
+    pub benchmark: Option<Benchmark>,
+    // number of worker threads, sequential when not set
+    pub jobs: Option<usize>,
+    // time budgets, unlimited when not set
+    pub day_timeout: Option<Duration>,
+    pub part_timeout: Option<Duration>,
+    // answers are looked up before running each part, and the new ones inserted
+    pub cache: Option<Cache>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Benchmark {
+    pub runs: usize,
+    pub warmup: usize,
+}
+
+impl Options {
+    fn runs(&self, part: Part) -> bool {
+        self.part.map(|selected| selected == part).unwrap_or(true)
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        vec![Part::First, Part::Second]
+            .into_iter()
+            .filter(|&part| self.runs(part))
+            .collect()
+    }
+}
+
+macro_rules! elapsed {
+    ($expression:expr) => {{
+        let clock = Instant::now();
+        let result = $expression;
+        (result, clock.elapsed())
+    }};
+}
+
+pub fn build_report(advent: Advent, day_numbers: &[usize], options: &Options) -> Report {
+    let (days, elapsed) = elapsed!(match options.jobs {
+        Some(jobs) => build_parallel(&advent, day_numbers, options, jobs),
+        None => day_numbers
+            .iter()
+            .map(|&number| build_day_result(&advent, number, options))
+            .collect(),
+    });
+
+    Report {
+        days,
+        elapsed,
+        benchmark: options.benchmark.is_some(),
+    }
+}
+
+// each worker takes the next day as soon as it is free,
+// results go back to the slot of their day to keep the order
+fn build_parallel(
+    advent: &Advent,
+    day_numbers: &[usize],
+    options: &Options,
+    jobs: usize,
+) -> Vec<DayResult> {
+    let next = AtomicUsize::new(0);
+    let slots = Mutex::new(day_numbers.iter().map(|_| None).collect::<Vec<_>>());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(day_numbers.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let number = match day_numbers.get(i) {
+                    Some(&number) => number,
+                    None => break,
+                };
+
+                let result = build_day_result(advent, number, options);
+                slots.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+
+    slots
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("aaaah! every day has been run"))
+        .collect()
+}
+
+// sent by the thread running a day at the end of each phase:
+// the load has no result, cpu time is the total up to that point
+struct Phase {
+    result: Option<Result<Answer>>,
+    elapsed: Duration,
+    cpu_elapsed: Duration,
+    memory: Option<Memory>,
+}
+
+// Err with the time waited when the phase ran out of time or its thread panicked
+type PhaseOutcome = std::result::Result<Phase, (Error, Duration)>;
+
+// a thread can't be killed, so a day that runs out of time is cancelled and left behind:
+// only the days calling `cancel::check` in their long loops actually stop, the others keep
+// a cpu busy in the background until they are done
+fn spawn_day(
+    advent: Advent,
+    number: usize,
+    parts: Vec<Part>,
+    token: CancelToken,
+) -> Receiver<Phase> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        token.install();
+        let cpu_clock = ThreadTime::now();
+
+        // nobody listens anymore after a timeout
+        let send = |result, elapsed, memory| {
+            let cpu_elapsed = cpu_clock.elapsed();
+            let _ = sender.send(Phase {
+                result,
+                elapsed,
+                cpu_elapsed,
+                memory,
+            });
+        };
+
+        let ((day, elapsed), memory) = memory::measure(|| elapsed!(advent.load_day(number)));
+        send(None, elapsed, memory);
+
+        for part in parts {
+            // a day that fails to load fails its challenges too
+            let ((result, elapsed), memory) = match &day {
+                Ok(day) => memory::measure(|| elapsed!(part.solve(day.as_ref()))),
+                Err(error) => ((Err(error.clone()), Duration::ZERO), None),
+            };
+            send(Some(result), elapsed, memory);
+        }
+    });
+
+    receiver
+}
+
+fn wait_phases(receiver: Receiver<Phase>, parts: usize, options: &Options) -> Vec<PhaseOutcome> {
+    let day_deadline = options.day_timeout.map(|budget| Instant::now() + budget);
+    let mut outcomes = Vec::new();
+
+    // the load counts only towards the budget of the day
+    for i in 0..=parts {
+        let clock = Instant::now();
+
+        let part_budget = options.part_timeout.filter(|_| i > 0);
+        let day_budget = day_deadline.map(|deadline| deadline.saturating_duration_since(clock));
+        let budget = part_budget.into_iter().chain(day_budget).min();
+
+        let phase = match budget {
+            Some(budget) => receiver.recv_timeout(budget),
+            None => receiver.recv().map_err(RecvTimeoutError::from),
+        };
+
+        let error = match phase {
+            Ok(phase) => {
+                outcomes.push(Ok(phase));
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => Error::Timeout,
+            // the sender is dropped before the last phase only when the thread unwinds
+            Err(RecvTimeoutError::Disconnected) => Error::Panicked,
+        };
+
+        // the phases after it are never run, and fail the same way
+        outcomes.push(Err((error.clone(), clock.elapsed())));
+        outcomes.resize_with(parts + 1, || Err((error.clone(), Duration::ZERO)));
+        break;
+    }
+
+    outcomes
+}
+
+fn build_day_result(advent: &Advent, number: usize, options: &Options) -> DayResult {
+    // an input that can't be read fails the load anyway
+    let cache = options
+        .cache
+        .as_ref()
+        .and_then(|cache| Some((cache, advent.read_input(number).ok()?)));
+
+    let mut cached = Vec::new();
+    let mut parts = Vec::new();
+    for part in options.parts() {
+        match cache
+            .as_ref()
+            .and_then(|(cache, input)| cache.get(number, part, input))
+        {
+            Some(answer) => cached.push((part, answer)),
+            None => parts.push(part),
+        }
+    }
+
+    // nothing left to load the day for
+    let outcomes = if parts.is_empty() {
+        vec![Ok(Phase {
+            result: None,
+            elapsed: Duration::ZERO,
+            cpu_elapsed: Duration::ZERO,
+            memory: None,
+        })]
+    } else {
+        let token = CancelToken::default();
+        let receiver = spawn_day(advent.clone(), number, parts.clone(), token.clone());
+
+        let outcomes = wait_phases(receiver, parts.len(), options);
+        if outcomes.iter().any(|outcome| outcome.is_err()) {
+            token.cancel();
+        }
+        outcomes
+    };
+    let interrupted = outcomes.iter().any(|outcome| outcome.is_err());
+
+    let (load_elapsed, load_memory) = match &outcomes[0] {
+        Ok(phase) => (phase.elapsed, phase.memory),
+        Err((_, waited)) => (*waited, None),
+    };
+
+    let cpu_elapsed = outcomes
+        .iter()
+        .rev()
+        .find_map(|outcome| outcome.as_ref().ok())
+        .map(|phase| phase.cpu_elapsed)
+        .unwrap_or_default();
+
+    let solved = |part: Part| {
+        if let Some((_, answer)) = cached.iter().find(|&&(p, _)| p == part) {
+            return (Some(Ok(answer.clone())), Duration::ZERO, None);
+        }
+
+        match parts.iter().position(|&p| p == part) {
+            Some(i) => match &outcomes[i + 1] {
+                Ok(phase) => (phase.result.clone(), phase.elapsed, phase.memory),
+                Err((error, waited)) => (Some(Err(error.clone())), *waited, None),
+            },
+            None => (None, Duration::ZERO, None),
+        }
+    };
+
+    let (first_result, first_elapsed, first_memory) = solved(Part::First);
+    let (second_result, second_elapsed, second_memory) = solved(Part::Second);
+
+    // only answers are worth keeping, errors may go away with a longer timeout
+    if let Some((cache, input)) = &cache {
+        for (part, result) in [(Part::First, &first_result), (Part::Second, &second_result)] {
+            match result {
+                Some(Ok(answer)) if parts.contains(&part) => {
+                    cache.insert(number, part, input, answer.clone())
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let is_cached = |part: Part| cached.iter().any(|&(p, _)| p == part);
+
+    let mut result = DayResult {
+        number,
+        first_result,
+        second_result,
+        load_elapsed,
+        first_elapsed,
+        second_elapsed,
+        load_stats: None,
+        first_stats: None,
+        second_stats: None,
+        load_memory,
+        first_memory,
+        second_memory,
+        first_verdict: None,
+        second_verdict: None,
+        first_cached: is_cached(Part::First),
+        second_cached: is_cached(Part::Second),
+        cpu_elapsed,
+    };
+
+    // a day that can't make it in time once won't make it many times,
+    // and one that panicked would take the benchmark down with it
+    if let Some(benchmark) = options.benchmark.filter(|_| !interrupted) {
+        let [load, first, second] = run_benchmark(advent, number, options, benchmark);
+        result.load_stats = Stats::from_samples(&load);
+        result.first_stats = Stats::from_samples(&first);
+        result.second_stats = Stats::from_samples(&second);
+    }
+
+    result
+}
+
+// the results are already known, so here only the samples of each phase matter
+fn run_benchmark(
+    advent: &Advent,
+    number: usize,
+    options: &Options,
+    benchmark: Benchmark,
+) -> [Vec<Duration>; 3] {
+    let mut samples = [vec![], vec![], vec![]];
+
+    for run in 0..benchmark.warmup + benchmark.runs {
+        let warm = run >= benchmark.warmup;
+
+        let (day, load_elapsed) = elapsed!(advent.load_day(number));
+        if warm {
+            samples[0].push(load_elapsed);
+        }
+
+        // challenges of a broken day have nothing to measure
+        let day = match day {
+            Ok(day) => day,
+            Err(_) => continue,
+        };
+
+        for (i, &part) in [Part::First, Part::Second].iter().enumerate() {
+            if options.runs(part) {
+                let (_, elapsed) = elapsed!(part.solve(day.as_ref()));
+                if warm {
+                    samples[i + 1].push(elapsed);
+                }
+            }
+        }
+    }
+
+    samples
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(report: &Report) -> Vec<(usize, Option<Result<Answer>>)> {
+        report
+            .days
+            .iter()
+            .map(|day| (day.number, day.second_result.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parallel_order() {
+        let days = [5, 1, 3, 30, 2];
+        let mut options = Options::default();
+        let sequential = build_report(Advent::new("src/input".to_string()), &days, &options);
+
+        options.jobs = Some(3);
+        let parallel = build_report(Advent::new("src/input".to_string()), &days, &options);
+
+        assert_eq!(answers(&parallel), answers(&sequential));
+        assert_eq!(
+            parallel
+                .days
+                .iter()
+                .map(|day| day.number)
+                .collect::<Vec<_>>(),
+            days
+        );
+    }
+
+    #[test]
+    fn test_panicked() {
+        // a day that loads fine and then panics in its first part
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let phase = Phase {
+                result: None,
+                elapsed: Duration::from_nanos(1),
+                cpu_elapsed: Duration::from_nanos(1),
+                memory: None,
+            };
+            sender.send(phase).unwrap();
+            panic!("aaaah!");
+        });
+
+        let outcomes = wait_phases(receiver, 2, &Options::default());
+        assert!(outcomes[0].is_ok());
+        assert_eq!(
+            outcomes[1..]
+                .iter()
+                .map(|outcome| outcome.as_ref().err().map(|(error, _)| error.clone()))
+                .collect::<Vec<_>>(),
+            vec![Some(Error::Panicked), Some(Error::Panicked)]
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let options = Options {
+            part: Some(Part::Second),
+            part_timeout: Some(Duration::from_millis(1)),
+            ..Default::default()
+        };
+
+        let report = build_report(Advent::new("src/input".to_string()), &[15, 30], &options);
+        assert_eq!(report.days[0].first_result, None);
+        assert_eq!(report.days[0].second_result, Some(Err(Error::Timeout)));
+        assert_eq!(
+            report.days[1].second_result,
+            Some(Err(Error::UnknownDay(30)))
+        );
+        assert_eq!(report.days[0].second_memory, None);
+    }
+
+    #[test]
+    fn test_cache() {
+        let path = std::env::temp_dir().join(format!("aoc2020_report_{}", std::process::id()));
+        let cache = Cache::new(path.to_str().unwrap(), "test");
+        cache.insert(1, Part::First, "1721\n979\n366\n299\n675\n1456", 42.into());
+
+        let advent = Advent::new("nowhere".to_string())
+            .with_input(1, "1721\n979\n366\n299\n675\n1456".to_string());
+        let options = Options {
+            cache: Some(cache),
+            ..Default::default()
+        };
+
+        // the cached part is not run, the other one is cached from now on
+        let report = build_report(advent.clone(), &[1], &options);
+        let day = &report.days[0];
+        assert_eq!(day.first_result, Some(Ok(42.into())));
+        assert!(day.first_cached && !day.second_cached && !day.is_cached());
+        assert_eq!(day.second_result, Some(Ok(241861950.into())));
+
+        let report = build_report(advent, &[1], &options);
+        let day = &report.days[0];
+        assert!(day.second_cached && day.is_cached());
+        assert_eq!(day.load_elapsed, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_memory() {
+        let options = Options {
+            part: Some(Part::First),
+            ..Default::default()
+        };
+
+        let report = build_report(Advent::new("src/input".to_string()), &[1], &options);
+        let day = &report.days[0];
+        // the numbers are kept by the day, the first part only needs its set
+        assert!(day.load_memory.unwrap().peak > 0);
+        assert!(day.first_memory.unwrap().total > 0);
+        assert_eq!(day.second_memory, None);
+    }
+}
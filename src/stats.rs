@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub std_dev: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub const NAMES: [&'static str; 5] = ["min", "median", "mean", "stddev", "p95"];
+
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+
+        let nanos = sorted
+            .iter()
+            .map(|d| d.as_nanos() as f64)
+            .collect::<Vec<_>>();
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+        let variance = nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64;
+
+        // middle indexes match when n is odd
+        let median = (sorted[(n - 1) / 2] + sorted[n / 2]) / 2;
+
+        // nearest rank
+        let p95 = sorted[(n as f64 * 0.95).ceil() as usize - 1];
+
+        Some(Self {
+            min: sorted[0],
+            median,
+            mean: from_nanos(mean),
+            std_dev: from_nanos(variance.sqrt()),
+            p95,
+        })
+    }
+
+    // same order of NAMES
+    pub fn values(&self) -> [Duration; 5] {
+        [self.min, self.median, self.mean, self.std_dev, self.p95]
+    }
+}
+
+fn from_nanos(nanos: f64) -> Duration {
+    Duration::from_nanos(nanos.round() as u64)
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(nanos: &[u64]) -> Vec<Duration> {
+        nanos.iter().map(|&n| Duration::from_nanos(n)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&samples(&[4, 2, 9, 5, 5, 4, 7, 4])).unwrap();
+        assert_eq!(stats.min, Duration::from_nanos(2));
+        assert_eq!(stats.median, Duration::from_nanos(4));
+        assert_eq!(stats.mean, Duration::from_nanos(5));
+        assert_eq!(stats.std_dev, Duration::from_nanos(2));
+        assert_eq!(stats.p95, Duration::from_nanos(9));
+    }
+
+    #[test]
+    fn test_single_sample() {
+        let stats = Stats::from_samples(&samples(&[3])).unwrap();
+        assert_eq!(stats.min, stats.p95);
+        assert_eq!(stats.median, Duration::from_nanos(3));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_p95() {
+        let stats = Stats::from_samples(&samples(&(1..=100).collect::<Vec<_>>())).unwrap();
+        assert_eq!(stats.p95, Duration::from_nanos(95));
+        assert_eq!(stats.median, Duration::from_nanos(50));
+    }
+
+    #[test]
+    fn test_no_samples() {
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+}
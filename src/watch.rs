@@ -0,0 +1,86 @@
+use std::{
+    fs,
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+// Polls the files instead of asking the OS to be notified: it works everywhere
+// and a few stats every now and then cost nothing.
+// The size is compared too, the modification time may be too coarse to tell
+// two quick saves apart.
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<Stamp>)>,
+}
+
+type Stamp = (SystemTime, u64);
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let stamp = stamp(&path);
+                (path, stamp)
+            })
+            .collect();
+
+        Self { files }
+    }
+
+    // true if any file was modified, created or removed since the last call
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+
+        for (path, last) in self.files.iter_mut() {
+            let current = stamp(path);
+            if current != *last {
+                *last = current;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    pub fn wait(&mut self, interval: Duration) {
+        while !self.changed() {
+            thread::sleep(interval);
+        }
+    }
+}
+
+// None when the file does not exist (yet)
+fn stamp(path: &PathBuf) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/* tests */
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    #[test]
+    fn test_changed() {
+        let path = env::temp_dir().join(format!("aoc2020_watch_{}", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut watcher = Watcher::new(vec![path.clone()]);
+        assert!(!watcher.changed());
+
+        fs::write(&path, "1").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        fs::write(&path, "12").unwrap();
+        assert!(watcher.changed());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+    }
+}